@@ -0,0 +1,309 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character::CharacterId,
+    enemy::{EnemyKilledEvent, EnemyType},
+    level::PlayerXP,
+    menu::{self, MenuActivated},
+    profile::Profile,
+    projectile::ProjectileKind,
+    state::{GameState, PauseState, RunStats, RunTimer},
+    upgrade::UpgradeEffect,
+    weapon::WeaponStats,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum AchievementId {
+    BoneCollector,
+    OrcSlayer,
+    MoonHunter,
+    Survivor,
+    IceAge,
+    Barrage,
+}
+
+impl AchievementId {
+    pub const ALL: [AchievementId; 6] = [
+        AchievementId::BoneCollector,
+        AchievementId::OrcSlayer,
+        AchievementId::MoonHunter,
+        AchievementId::Survivor,
+        AchievementId::IceAge,
+        AchievementId::Barrage,
+    ];
+}
+
+pub enum Condition {
+    /// Lifetime kills, of one enemy type or of any.
+    Kills { enemy: Option<EnemyType>, count: u32 },
+    /// Reach a level within a single run, optionally without ever switching shot type.
+    ReachLevel { level: u32, only_shot: Option<ProjectileKind> },
+    SurviveSeconds(f32),
+    Multishot(u32),
+}
+
+pub enum Reward {
+    Character(CharacterId),
+    Upgrade(UpgradeEffect),
+}
+
+pub struct AchievementDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+    pub reward: Option<Reward>,
+}
+
+impl AchievementId {
+    pub fn definition(&self) -> AchievementDefinition {
+        match self {
+            AchievementId::BoneCollector => AchievementDefinition {
+                name: "Bone Collector",
+                description: "Kill 100 Skeletons",
+                condition: Condition::Kills {
+                    enemy: Some(EnemyType::Skeleton),
+                    count: 100,
+                },
+                reward: Some(Reward::Character(CharacterId::Skeleton)),
+            },
+            AchievementId::OrcSlayer => AchievementDefinition {
+                name: "Orc Slayer",
+                description: "Kill 1000 Orcs",
+                condition: Condition::Kills {
+                    enemy: Some(EnemyType::Orc),
+                    count: 1000,
+                },
+                reward: Some(Reward::Character(CharacterId::Orc)),
+            },
+            AchievementId::MoonHunter => AchievementDefinition {
+                name: "Moon Hunter",
+                description: "Kill 250 Werewolves",
+                condition: Condition::Kills {
+                    enemy: Some(EnemyType::Werewolf),
+                    count: 250,
+                },
+                reward: Some(Reward::Character(CharacterId::Werewolf)),
+            },
+            AchievementId::Survivor => AchievementDefinition {
+                name: "Survivor",
+                description: "Survive for 5 minutes",
+                condition: Condition::SurviveSeconds(300.0),
+                reward: Some(Reward::Upgrade(UpgradeEffect::ChangeShotType(
+                    ProjectileKind::Piercing,
+                ))),
+            },
+            AchievementId::IceAge => AchievementDefinition {
+                name: "Ice Age",
+                description: "Reach level 20 with only Ice shots",
+                condition: Condition::ReachLevel {
+                    level: 20,
+                    only_shot: Some(ProjectileKind::Ice),
+                },
+                reward: Some(Reward::Upgrade(UpgradeEffect::IncreaseDashTrailDamage(15.0))),
+            },
+            AchievementId::Barrage => AchievementDefinition {
+                name: "Barrage",
+                description: "Fire 6 projectiles at once",
+                condition: Condition::Multishot(6),
+                reward: None,
+            },
+        }
+    }
+}
+
+impl Profile {
+    pub fn has_achievement(&self, id: AchievementId) -> bool {
+        self.achievements.contains(&id)
+    }
+
+    /// The achievement that still has to be earned to use `character`, if any.
+    pub fn character_lock(&self, character: CharacterId) -> Option<AchievementId> {
+        AchievementId::ALL.into_iter().find(|id| {
+            !self.has_achievement(*id)
+                && matches!(id.definition().reward, Some(Reward::Character(c)) if c == character)
+        })
+    }
+
+    pub fn is_upgrade_unlocked(&self, upgrade: &UpgradeEffect) -> bool {
+        AchievementId::ALL.into_iter().all(|id| {
+            self.has_achievement(id)
+                || !matches!(&id.definition().reward, Some(Reward::Upgrade(u)) if u == upgrade)
+        })
+    }
+}
+
+impl Reward {
+    fn label(&self) -> String {
+        match self {
+            Reward::Character(id) => format!("unlocks {}", id.definition().name),
+            Reward::Upgrade(UpgradeEffect::ChangeShotType(kind)) => {
+                format!("unlocks {:?} shot", kind)
+            }
+            Reward::Upgrade(_) => "unlocks a new upgrade".to_string(),
+        }
+    }
+}
+
+/// Shown briefly at the top of the screen when an achievement unlocks.
+#[derive(Component)]
+struct AchievementToast(Timer);
+
+#[derive(Component)]
+struct AchievementsBackButton;
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Achievements), spawn_achievements_screen)
+            .add_systems(
+                Update,
+                handle_achievements_back.run_if(in_state(GameState::Achievements)),
+            )
+            .add_systems(
+                Update,
+                (track_run_stats, check_achievements, update_toasts)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn track_run_stats(
+    mut kills: EventReader<EnemyKilledEvent>,
+    mut run_stats: ResMut<RunStats>,
+    mut profile: ResMut<Profile>,
+) {
+    for ev in kills.read() {
+        *run_stats.kills.entry(ev.enemy_type).or_default() += 1;
+        *profile.kills.entry(ev.enemy_type).or_default() += 1;
+    }
+}
+
+fn condition_met(
+    condition: &Condition,
+    profile: &Profile,
+    run_stats: &RunStats,
+    run_timer: &RunTimer,
+    xp: &PlayerXP,
+    weapon_stats: &WeaponStats,
+) -> bool {
+    match condition {
+        Condition::Kills { enemy: Some(enemy), count } => {
+            profile.kills.get(enemy).copied().unwrap_or(0) >= *count
+        }
+        Condition::Kills { enemy: None, count } => profile.kills.values().sum::<u32>() >= *count,
+        Condition::ReachLevel { level, only_shot } => {
+            xp.level >= *level
+                && only_shot.is_none_or(|shot| {
+                    run_stats.shot_types.len() == 1 && run_stats.shot_types.contains(&shot)
+                })
+        }
+        Condition::SurviveSeconds(secs) => run_timer.0.elapsed_secs() >= *secs,
+        Condition::Multishot(count) => weapon_stats.multishot >= *count,
+    }
+}
+
+fn check_achievements(
+    mut commands: Commands,
+    mut profile: ResMut<Profile>,
+    run_stats: Res<RunStats>,
+    run_timer: Res<RunTimer>,
+    xp: Res<PlayerXP>,
+    weapon_stats: Res<WeaponStats>,
+) {
+    let mut unlocked = false;
+    for id in AchievementId::ALL {
+        if profile.has_achievement(id) {
+            continue;
+        }
+        let def = id.definition();
+        if condition_met(&def.condition, &profile, &run_stats, &run_timer, &xp, &weapon_stats) {
+            info!("Achievement unlocked: {}", def.name);
+            profile.achievements.insert(id);
+            unlocked = true;
+            commands.spawn((
+                TextBundle::from_section(
+                    format!("Achievement unlocked: {}", def.name),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(1.0, 0.85, 0.2),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(60.0),
+                    left: Val::Px(10.0),
+                    ..default()
+                }),
+                AchievementToast(Timer::from_seconds(3.0, TimerMode::Once)),
+                StateScoped(GameState::Playing),
+            ));
+        }
+    }
+    if unlocked {
+        profile.save();
+    }
+}
+
+fn update_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in q.iter_mut() {
+        toast.0.tick(time.delta());
+        if toast.0.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn spawn_achievements_screen(mut commands: Commands, profile: Res<Profile>) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::Achievements)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Achievements");
+            for id in AchievementId::ALL {
+                let def = id.definition();
+                let unlocked = profile.has_achievement(id);
+                let reward = def
+                    .reward
+                    .map(|r| format!(" - {}", r.label()))
+                    .unwrap_or_default();
+                parent.spawn(TextBundle::from_section(
+                    format!(
+                        "[{}] {}: {}{}",
+                        if unlocked { "x" } else { " " },
+                        def.name,
+                        def.description,
+                        reward
+                    ),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: if unlocked {
+                            Color::srgb(1.0, 0.85, 0.2)
+                        } else {
+                            Color::srgb(0.6, 0.6, 0.6)
+                        },
+                        ..default()
+                    },
+                ));
+            }
+            menu::spawn_button(parent, 0, "Back", AchievementsBackButton);
+        });
+}
+
+fn handle_achievements_back(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<(), With<AchievementsBackButton>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        if button_q.contains(ev.0) {
+            next_state.set(GameState::MainMenu);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! A damaging aura around the player. Every interval it hurts every enemy inside it and
+//! nudges them back a little. Its radius grows with the player's area stat.
+
+use bevy::prelude::*;
+
+use crate::{
+    enemy::Enemy,
+    health::{DamageEvent, DamageType},
+    player::{Player, PlayerStats},
+    shapes::CircleTexture,
+    state::{GameState, PauseState, RunRng},
+    weapon::{WeaponKind, WeaponStats},
+};
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct AuraStats {
+    pub damage: f32, // Per tick; 0 until the weapon is picked up
+    pub radius: f32,
+    pub interval: f32,
+    pub knockback: f32,
+}
+
+impl Default for AuraStats {
+    fn default() -> Self {
+        Self {
+            damage: 0.0,
+            radius: 60.0,
+            interval: 0.75,
+            knockback: 40.0,
+        }
+    }
+}
+
+impl AuraStats {
+    /// Radius after the player's area bonus.
+    fn effective_radius(&self, area: f32) -> f32 {
+        self.radius * area
+    }
+}
+
+#[derive(Resource)]
+struct AuraTimer(Timer);
+
+impl Default for AuraTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(AuraStats::default().interval, TimerMode::Repeating))
+    }
+}
+
+#[derive(Component)]
+struct Aura;
+
+pub struct AuraPlugin;
+
+impl Plugin for AuraPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AuraStats::default())
+            .insert_resource(AuraTimer::default())
+            .init_resource::<CircleTexture>()
+            .register_type::<AuraStats>()
+            .add_systems(OnExit(GameState::Playing), reset_timer)
+            .add_systems(
+                Update,
+                (spawn_aura, follow_player, aura_damage)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn spawn_aura(
+    mut commands: Commands,
+    stats: Res<AuraStats>,
+    circle: Res<CircleTexture>,
+    aura_q: Query<(), With<Aura>>,
+) {
+    if stats.damage <= 0.0 || !aura_q.is_empty() {
+        return;
+    }
+    commands.spawn((
+        SpriteBundle {
+            texture: circle.0.clone(),
+            sprite: Sprite {
+                color: Color::srgba(0.9, 0.95, 0.7, 0.25),
+                ..default()
+            },
+            ..default()
+        },
+        Aura,
+        Name::new("Aura"),
+        StateScoped(GameState::Playing),
+    ));
+}
+
+#[allow(clippy::type_complexity)]
+fn follow_player(
+    stats: Res<AuraStats>,
+    player_stats: Res<PlayerStats>,
+    player_q: Query<&Transform, With<Player>>,
+    mut aura_q: Query<(&mut Transform, &mut Sprite), (With<Aura>, Without<Player>)>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    for (mut transform, mut sprite) in aura_q.iter_mut() {
+        // Just under the player and enemies
+        transform.translation = player_t.translation.truncate().extend(-0.5);
+        sprite.custom_size = Some(Vec2::splat(stats.effective_radius(player_stats.area) * 2.0));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn aura_damage(
+    time: Res<Time>,
+    stats: Res<AuraStats>,
+    player_stats: Res<PlayerStats>,
+    weapon_stats: Res<WeaponStats>,
+    mut timer: ResMut<AuraTimer>,
+    mut run_rng: ResMut<RunRng>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    player_q: Query<(Entity, &Transform), With<Player>>,
+    enemy_q: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    if stats.damage <= 0.0 {
+        return;
+    }
+    timer
+        .0
+        .set_duration(std::time::Duration::from_secs_f32(stats.interval));
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok((player_e, player_t)) = player_q.get_single() else {
+        return;
+    };
+
+    let player_pos = player_t.translation.truncate();
+    let radius = stats.effective_radius(player_stats.area);
+    for (enemy_e, enemy_t) in enemy_q.iter() {
+        let offset = enemy_t.translation.truncate() - player_pos;
+        if offset.length() >= radius {
+            continue;
+        }
+        let (amount, crit) = weapon_stats.roll_hit(&mut run_rng.rng, stats.damage);
+        damage_writer.send(DamageEvent {
+            entity: enemy_e,
+            amount,
+            source: Some(player_e),
+            damage_type: DamageType::Physical,
+            crit,
+            knockback: offset.normalize_or_zero() * stats.knockback,
+            weapon: Some(WeaponKind::Aura),
+        });
+    }
+}
+
+fn reset_timer(mut timer: ResMut<AuraTimer>) {
+    *timer = AuraTimer::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radius_scales_with_area() {
+        let stats = AuraStats::default();
+        assert_eq!(stats.effective_radius(1.0), 60.0);
+        assert_eq!(stats.effective_radius(1.5), 90.0);
+
+        let upgraded = AuraStats {
+            radius: 80.0,
+            ..default()
+        };
+        assert_eq!(upgraded.effective_radius(1.25), 100.0);
+    }
+}
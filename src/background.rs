@@ -0,0 +1,295 @@
+//! Endless tiled ground. The world is split into square chunks of tiles; chunks are
+//! spawned as they come near the camera and despawned once they are far behind it.
+//! Each tile is picked from a seeded noise field, so the same run seed always lays
+//! out the same ground. The tileset itself is painted per stage from its ground style.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    utils::HashMap,
+};
+
+use crate::{
+    run_setup::RunSetup,
+    stage::StageBounds,
+    state::{GameState, RunRng},
+};
+
+/// Pixels per tile in the tileset texture.
+const TILE_TEXELS: u32 = 16;
+/// On-screen size of a tile.
+pub const TILE_SIZE: f32 = 32.0;
+/// Tiles along each side of a chunk.
+pub const CHUNK_TILES: i32 = 16;
+const CHUNK_SIZE: f32 = TILE_SIZE * CHUNK_TILES as f32;
+/// Extra chunks kept loaded beyond the edge of the screen.
+const CHUNK_MARGIN: i32 = 1;
+
+/// Look of a stage's ground; every style paints the same four tiles.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Ground {
+    Grass,
+    Flagstone,
+}
+
+/// Tiles in the tileset, in atlas order. Named for the grass style; other styles
+/// repaint them in their own colours.
+#[derive(Clone, Copy)]
+enum Tile {
+    Grass,
+    DarkGrass,
+    Dirt,
+    Flowers,
+}
+
+impl Tile {
+    const ALL: [Tile; 4] = [Tile::Grass, Tile::DarkGrass, Tile::Dirt, Tile::Flowers];
+
+    /// Base colour and how much each texel may vary from it.
+    fn palette(&self, ground: Ground) -> ([u8; 3], u8) {
+        match (ground, self) {
+            (Ground::Grass, Tile::Grass | Tile::Flowers) => ([70, 120, 50], 12),
+            (Ground::Grass, Tile::DarkGrass) => ([50, 95, 40], 10),
+            (Ground::Grass, Tile::Dirt) => ([115, 90, 60], 14),
+            (Ground::Flagstone, Tile::Grass | Tile::Flowers) => ([120, 115, 105], 10),
+            (Ground::Flagstone, Tile::DarkGrass) => ([95, 90, 85], 8),
+            (Ground::Flagstone, Tile::Dirt) => ([170, 150, 110], 14),
+        }
+    }
+
+    /// Colour of the sparse speckles on the detail tile.
+    fn detail(ground: Ground) -> [u8; 3] {
+        match ground {
+            Ground::Grass => [230, 220, 90],
+            Ground::Flagstone => [60, 55, 50],
+        }
+    }
+}
+
+#[derive(Resource)]
+struct Tileset {
+    texture: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+/// Chunks currently spawned, by chunk coordinate.
+#[derive(Resource, Default)]
+struct LoadedChunks(HashMap<IVec2, Entity>);
+
+/// Root of a chunk's tiles, at its chunk coordinate. Anything else that belongs to the
+/// chunk (e.g. obstacles) is parented to it so it unloads along with it.
+#[derive(Component)]
+pub struct BackgroundChunk(pub IVec2);
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LoadedChunks::default())
+            .add_systems(OnEnter(GameState::Playing), build_tileset)
+            .add_systems(OnExit(GameState::Playing), forget_chunks)
+            .add_systems(
+                Update,
+                (unload_chunks, load_chunks)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Hash of a lattice point, uniform in 0..1.
+pub fn hash(x: i32, y: i32, seed: u64) -> f32 {
+    let mut h = seed ^ (x as u32 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    h ^= (y as u32 as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Smoothly interpolated value noise, roughly `scale` tiles per feature.
+fn value_noise(x: f32, y: f32, scale: f32, seed: u64) -> f32 {
+    let (x, y) = (x / scale, y / scale);
+    let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, ty) = (smooth(x - x0 as f32), smooth(y - y0 as f32));
+
+    let top = hash(x0, y0, seed) * (1.0 - tx) + hash(x0 + 1, y0, seed) * tx;
+    let bottom = hash(x0, y0 + 1, seed) * (1.0 - tx) + hash(x0 + 1, y0 + 1, seed) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
+
+fn tile_at(tile: IVec2, seed: u64) -> Tile {
+    let (x, y) = (tile.x as f32, tile.y as f32);
+    let ground = value_noise(x, y, 12.0, seed) * 0.7 + value_noise(x, y, 4.0, seed ^ 1) * 0.3;
+    if ground < 0.3 {
+        Tile::Dirt
+    } else if ground > 0.65 {
+        Tile::DarkGrass
+    } else if hash(tile.x, tile.y, seed ^ 2) < 0.04 {
+        Tile::Flowers
+    } else {
+        Tile::Grass
+    }
+}
+
+/// Paints the stage's tileset texture: one row of `TILE_TEXELS`-square tiles.
+fn build_tileset(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    setup: Res<RunSetup>,
+) {
+    let ground = setup.stage.definition().ground;
+    let width = TILE_TEXELS * Tile::ALL.len() as u32;
+    let mut data = Vec::with_capacity((width * TILE_TEXELS * 4) as usize);
+    for y in 0..TILE_TEXELS {
+        for x in 0..width {
+            let tile = Tile::ALL[(x / TILE_TEXELS) as usize];
+            let (base, variance) = tile.palette(ground);
+            let (tx, ty) = ((x % TILE_TEXELS) as i32, y as i32);
+            let jitter = (hash(tx, ty, x as u64 / TILE_TEXELS as u64) * 2.0 - 1.0) * variance as f32;
+            let mut rgb = base.map(|c| (c as f32 + jitter).clamp(0.0, 255.0) as u8);
+
+            // A few petals (or cracks) on the detail tile
+            if matches!(tile, Tile::Flowers) && hash(tx, ty, 99) < 0.06 {
+                rgb = Tile::detail(ground);
+            }
+            data.extend_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+        }
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width,
+            height: TILE_TEXELS,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::splat(TILE_TEXELS),
+        Tile::ALL.len() as u32,
+        1,
+        None,
+        None,
+    );
+    commands.insert_resource(Tileset {
+        texture: images.add(image),
+        layout: layouts.add(layout),
+    });
+}
+
+/// Chunk coordinates that should be loaded for a camera centred at `center`.
+fn visible_chunks(center: Vec2, window: Vec2) -> (IVec2, IVec2) {
+    let half = window / 2.0;
+    let min = ((center - half) / CHUNK_SIZE).floor().as_ivec2() - IVec2::splat(CHUNK_MARGIN);
+    let max = ((center + half) / CHUNK_SIZE).floor().as_ivec2() + IVec2::splat(CHUNK_MARGIN);
+    (min, max)
+}
+
+/// Chunks to keep loaded, limited to the arena on bounded stages.
+fn camera_view(
+    camera_q: &Query<&Transform, With<Camera>>,
+    window_q: &Query<&Window>,
+    setup: &RunSetup,
+) -> Option<(IVec2, IVec2)> {
+    let camera_t = camera_q.get_single().ok()?;
+    let window = window_q.get_single().ok()?;
+    let (min, max) = visible_chunks(camera_t.translation.truncate(), window.size());
+    Some(match setup.stage.definition().bounds {
+        StageBounds::Endless => (min, max),
+        StageBounds::Arena { half_size } => {
+            let arena_min = (-half_size / CHUNK_SIZE).floor().as_ivec2();
+            let arena_max = (half_size / CHUNK_SIZE).ceil().as_ivec2() - 1;
+            (min.max(arena_min), max.min(arena_max))
+        }
+    })
+}
+
+fn unload_chunks(
+    mut commands: Commands,
+    mut loaded: ResMut<LoadedChunks>,
+    camera_q: Query<&Transform, With<Camera>>,
+    window_q: Query<&Window>,
+    setup: Res<RunSetup>,
+) {
+    let Some((min, max)) = camera_view(&camera_q, &window_q, &setup) else {
+        return;
+    };
+    loaded.0.retain(|coord, entity| {
+        let keep = coord.cmpge(min).all() && coord.cmple(max).all();
+        if !keep {
+            commands.entity(*entity).despawn_recursive();
+        }
+        keep
+    });
+}
+
+fn load_chunks(
+    mut commands: Commands,
+    mut loaded: ResMut<LoadedChunks>,
+    tileset: Res<Tileset>,
+    run_rng: Res<RunRng>,
+    camera_q: Query<&Transform, With<Camera>>,
+    window_q: Query<&Window>,
+    setup: Res<RunSetup>,
+) {
+    let Some((min, max)) = camera_view(&camera_q, &window_q, &setup) else {
+        return;
+    };
+
+    for cx in min.x..=max.x {
+        for cy in min.y..=max.y {
+            let coord = IVec2::new(cx, cy);
+            if loaded.0.contains_key(&coord) {
+                continue;
+            }
+            let origin = coord.as_vec2() * CHUNK_SIZE;
+            let chunk = commands
+                .spawn((
+                    SpatialBundle::from_transform(Transform::from_translation(
+                        origin.extend(-10.0),
+                    )),
+                    BackgroundChunk(coord),
+                    StateScoped(GameState::Playing),
+                ))
+                .with_children(|parent| {
+                    for tx in 0..CHUNK_TILES {
+                        for ty in 0..CHUNK_TILES {
+                            let tile = coord * CHUNK_TILES + IVec2::new(tx, ty);
+                            let offset = (Vec2::new(tx as f32, ty as f32) + 0.5) * TILE_SIZE;
+                            parent.spawn((
+                                SpriteBundle {
+                                    sprite: Sprite {
+                                        custom_size: Some(Vec2::splat(TILE_SIZE)),
+                                        ..default()
+                                    },
+                                    texture: tileset.texture.clone(),
+                                    transform: Transform::from_translation(offset.extend(0.0)),
+                                    ..default()
+                                },
+                                TextureAtlas {
+                                    layout: tileset.layout.clone(),
+                                    index: tile_at(tile, run_rng.seed) as usize,
+                                },
+                            ));
+                        }
+                    }
+                })
+                .id();
+            loaded.0.insert(coord, chunk);
+        }
+    }
+}
+
+/// Chunk entities are state scoped; drop the bookkeeping so the next run starts fresh.
+fn forget_chunks(mut loaded: ResMut<LoadedChunks>) {
+    loaded.0.clear();
+}
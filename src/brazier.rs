@@ -0,0 +1,140 @@
+//! Breakable light sources scattered over the stage. Any projectile knocks one over and
+//! it drops a random pickup. Broken braziers stay broken for the rest of the run, even
+//! if their chunk unloads and loads again.
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    background::{self, BackgroundChunk, CHUNK_TILES, TILE_SIZE},
+    pickup,
+    projectile::{Projectile, ProjectileKind},
+    run_setup::RunSetup,
+    state::{GameState, PauseState, RunRng},
+};
+
+const BRAZIER_RADIUS: f32 = 12.0;
+
+#[derive(Component)]
+pub struct Brazier {
+    tile: IVec2,
+    /// World position; braziers are parented to their chunk.
+    center: Vec2,
+}
+
+#[derive(Component)]
+struct Flame;
+
+/// Tiles whose brazier has been broken this run.
+#[derive(Resource, Default)]
+struct BrokenBraziers(HashSet<IVec2>);
+
+pub struct BrazierPlugin;
+
+impl Plugin for BrazierPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BrokenBraziers::default())
+            .add_systems(OnExit(GameState::Playing), forget_broken_braziers)
+            .add_systems(
+                Update,
+                spawn_chunk_braziers.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (projectile_brazier_collision, flicker_flames).run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn spawn_chunk_braziers(
+    mut commands: Commands,
+    chunk_q: Query<(Entity, &BackgroundChunk), Added<BackgroundChunk>>,
+    setup: Res<RunSetup>,
+    run_rng: Res<RunRng>,
+    broken: Res<BrokenBraziers>,
+) {
+    let chance = setup.stage.definition().light_sources;
+
+    for (chunk_e, chunk) in chunk_q.iter() {
+        commands.entity(chunk_e).with_children(|parent| {
+            for tx in 0..CHUNK_TILES {
+                for ty in 0..CHUNK_TILES {
+                    let tile = chunk.0 * CHUNK_TILES + IVec2::new(tx, ty);
+                    if broken.0.contains(&tile)
+                        || background::hash(tile.x, tile.y, run_rng.seed ^ 0xb7a2) >= chance
+                    {
+                        continue;
+                    }
+                    let local = (Vec2::new(tx as f32, ty as f32) + 0.5) * TILE_SIZE;
+                    let center = chunk.0.as_vec2() * CHUNK_TILES as f32 * TILE_SIZE + local;
+                    parent
+                        .spawn((
+                            SpriteBundle {
+                                sprite: Sprite {
+                                    color: Color::srgb(0.35, 0.25, 0.2),
+                                    custom_size: Some(Vec2::new(14.0, 18.0)),
+                                    ..default()
+                                },
+                                transform: Transform::from_translation(local.extend(6.0)),
+                                ..default()
+                            },
+                            Brazier { tile, center },
+                            Name::new("Brazier"),
+                        ))
+                        .with_children(|brazier| {
+                            brazier.spawn((
+                                SpriteBundle {
+                                    sprite: Sprite {
+                                        color: Color::srgb(1.0, 0.6, 0.15),
+                                        custom_size: Some(Vec2::splat(10.0)),
+                                        ..default()
+                                    },
+                                    transform: Transform::from_xyz(0.0, 12.0, 0.1),
+                                    ..default()
+                                },
+                                Flame,
+                            ));
+                        });
+                }
+            }
+        });
+    }
+}
+
+fn projectile_brazier_collision(
+    mut commands: Commands,
+    projectile_q: Query<(Entity, &Transform, &Projectile)>,
+    brazier_q: Query<(Entity, &Brazier)>,
+    mut broken: ResMut<BrokenBraziers>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    for (proj_e, proj_t, proj) in projectile_q.iter() {
+        let proj_pos = proj_t.translation.truncate();
+        for (brazier_e, brazier) in brazier_q.iter() {
+            if broken.0.contains(&brazier.tile)
+                || proj_pos.distance(brazier.center) >= BRAZIER_RADIUS
+            {
+                continue;
+            }
+            broken.0.insert(brazier.tile);
+            commands.entity(brazier_e).despawn_recursive();
+            pickup::spawn_random_drop(&mut commands, brazier.center.extend(1.0), &mut run_rng.rng);
+            if proj.kind != ProjectileKind::Piercing {
+                commands.entity(proj_e).despawn();
+            }
+            break;
+        }
+    }
+}
+
+fn flicker_flames(time: Res<Time>, mut flame_q: Query<(&mut Transform, &GlobalTransform), With<Flame>>) {
+    let t = time.elapsed_seconds();
+    for (mut transform, global) in flame_q.iter_mut() {
+        // Offset by position so neighbouring flames don't pulse in step
+        let phase = global.translation().x * 0.13 + global.translation().y * 0.07;
+        transform.scale = Vec3::splat(1.0 + 0.15 * (t * 9.0 + phase).sin());
+    }
+}
+
+fn forget_broken_braziers(mut broken: ResMut<BrokenBraziers>) {
+    broken.0.clear();
+}
@@ -0,0 +1,92 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use rand::Rng;
+
+use crate::{
+    health::{DamageEvent, Invulnerable},
+    player::Player,
+    settings::Settings,
+    state::{GameState, PauseState},
+};
+
+/// Largest camera offset in pixels, reached at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+/// Trauma lost per second.
+const SHAKE_DECAY: f32 = 1.5;
+
+/// Screen shake builds up as trauma in 0..1 and is felt as its square, so small
+/// hits barely register while big ones stack into a strong shake.
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CameraShake::default())
+            .add_systems(OnExit(GameState::Playing), reset_camera)
+            .add_systems(
+                Update,
+                shake_on_player_hit.run_if(in_state(PauseState::Running)),
+            )
+            // After all movement this frame, before transforms are propagated for rendering
+            .add_systems(
+                PostUpdate,
+                follow_player
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn shake_on_player_hit(
+    mut damage_events: EventReader<DamageEvent>,
+    mut shake: ResMut<CameraShake>,
+    player_q: Query<(), (With<Player>, Without<Invulnerable>)>,
+) {
+    for ev in damage_events.read() {
+        if player_q.contains(ev.entity) {
+            shake.add_trauma(0.4);
+        }
+    }
+}
+
+/// Centres the camera on the player, plus any screen shake.
+fn follow_player(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut shake: ResMut<CameraShake>,
+    player_q: Query<&Transform, With<Player>>,
+    mut camera_q: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_seconds()).max(0.0);
+
+    // Purely cosmetic, so it doesn't draw from the run's seeded RNG.
+    let mut rng = rand::thread_rng();
+    let strength = MAX_SHAKE_OFFSET * shake.trauma * shake.trauma * settings.screen_shake;
+    let offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * strength;
+
+    for mut transform in camera_q.iter_mut() {
+        transform.translation.x = player_t.translation.x + offset.x;
+        transform.translation.y = player_t.translation.y + offset.y;
+    }
+}
+
+fn reset_camera(mut shake: ResMut<CameraShake>, mut camera_q: Query<&mut Transform, With<Camera>>) {
+    shake.trauma = 0.0;
+    for mut transform in camera_q.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+}
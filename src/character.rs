@@ -0,0 +1,242 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::Health,
+    level::LevelUpEvent,
+    menu::{self, MenuActivated},
+    player::{Player, PlayerStats},
+    profile::Profile,
+    projectile::ProjectileKind,
+    state::{GameState, PauseState},
+    upgrade::{UpgradeLevels, UpgradeSlot},
+    weapon::WeaponStats,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub enum CharacterId {
+    #[default]
+    Wizard,
+    Skeleton,
+    Orc,
+    Werewolf,
+}
+
+impl CharacterId {
+    pub const ALL: [CharacterId; 4] = [
+        CharacterId::Wizard,
+        CharacterId::Skeleton,
+        CharacterId::Orc,
+        CharacterId::Werewolf,
+    ];
+}
+
+/// Bonus a character gains every time they level up.
+#[derive(Clone, Copy)]
+pub enum PassiveBonus {
+    MoveSpeed(f32),
+    MaxHealth(f32),
+    ProjectileSpeed(f32),
+    Heal(f32),
+}
+
+#[derive(Clone)]
+pub struct CharacterDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub texture_path: &'static str,
+    pub frame_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+    pub scale: f32,
+    pub max_health: f32,
+    pub move_speed: f32,
+    pub starting_shot: ProjectileKind,
+    pub multishot: u32,
+    pub passive: PassiveBonus,
+}
+
+impl CharacterId {
+    pub fn definition(&self) -> CharacterDefinition {
+        match self {
+            CharacterId::Wizard => CharacterDefinition {
+                name: "Wizard",
+                description: "Balanced caster. +10 shot speed per level.",
+                texture_path: "Wizard.png",
+                frame_size: UVec2::splat(100),
+                columns: 8,
+                rows: 7,
+                scale: 1.5,
+                max_health: 100.0,
+                move_speed: 200.0,
+                starting_shot: ProjectileKind::Normal,
+                multishot: 1,
+                passive: PassiveBonus::ProjectileSpeed(10.0),
+            },
+            CharacterId::Skeleton => CharacterDefinition {
+                name: "Skeleton",
+                description: "Fragile ice archer with two shots. Heals 5 per level.",
+                texture_path: "Skeleton.png",
+                frame_size: UVec2::splat(100),
+                columns: 8,
+                rows: 7,
+                scale: 1.5,
+                max_health: 70.0,
+                move_speed: 190.0,
+                starting_shot: ProjectileKind::Ice,
+                multishot: 2,
+                passive: PassiveBonus::Heal(5.0),
+            },
+            CharacterId::Orc => CharacterDefinition {
+                name: "Orc",
+                description: "Slow fireball brute. +10 max HP per level.",
+                texture_path: "Orc.png",
+                frame_size: UVec2::splat(100),
+                columns: 8,
+                rows: 7,
+                scale: 1.5,
+                max_health: 150.0,
+                move_speed: 170.0,
+                starting_shot: ProjectileKind::Fireball,
+                multishot: 1,
+                passive: PassiveBonus::MaxHealth(10.0),
+            },
+            CharacterId::Werewolf => CharacterDefinition {
+                name: "Werewolf",
+                description: "Fast piercing hunter. +5 move speed per level.",
+                texture_path: "Werewolf.png",
+                frame_size: UVec2::splat(100),
+                columns: 8,
+                rows: 7,
+                scale: 1.5,
+                max_health: 90.0,
+                move_speed: 240.0,
+                starting_shot: ProjectileKind::Piercing,
+                multishot: 1,
+                passive: PassiveBonus::MoveSpeed(5.0),
+            },
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SelectedCharacter(pub CharacterId);
+
+#[derive(Component)]
+enum CharacterSelectButton {
+    Character(CharacterId),
+    Back,
+}
+
+pub struct CharacterPlugin;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SelectedCharacter::default())
+            .add_systems(OnEnter(GameState::Characters), spawn_character_select)
+            .add_systems(
+                Update,
+                handle_character_select.run_if(in_state(GameState::Characters)),
+            )
+            .add_systems(
+                Update,
+                apply_passive_bonus.run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+/// Applies the selected character's base stats on top of freshly reset run stats.
+pub fn apply_starting_kit(
+    selected: Res<SelectedCharacter>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+    mut levels: ResMut<UpgradeLevels>,
+) {
+    let def = selected.0.definition();
+    player_stats.move_speed = def.move_speed;
+    player_stats.max_health = def.max_health;
+    weapon_stats.current_shot_type = def.starting_shot;
+    weapon_stats.multishot = def.multishot;
+    levels.add(UpgradeSlot::Weapon(def.starting_shot));
+}
+
+fn spawn_character_select(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    selected: Res<SelectedCharacter>,
+) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::Characters)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Choose your character");
+            for (i, id) in CharacterId::ALL.into_iter().enumerate() {
+                let def = id.definition();
+                let label = match profile.character_lock(id) {
+                    Some(lock) => format!(
+                        "{}. {} - Locked: {}",
+                        i + 1,
+                        def.name,
+                        lock.definition().description
+                    ),
+                    None => format!(
+                        "{}. {}{} ({} HP) - {}",
+                        i + 1,
+                        def.name,
+                        if selected.0 == id { " [selected]" } else { "" },
+                        def.max_health,
+                        def.description
+                    ),
+                };
+                menu::spawn_button(parent, i, label, CharacterSelectButton::Character(id));
+            }
+            let n = CharacterId::ALL.len();
+            menu::spawn_button(
+                parent,
+                n,
+                format!("{}. Back", n + 1),
+                CharacterSelectButton::Back,
+            );
+        });
+}
+
+fn handle_character_select(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&CharacterSelectButton>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut next_state: ResMut<NextState<GameState>>,
+    profile: Res<Profile>,
+) {
+    for ev in activated.read() {
+        match button_q.get(ev.0) {
+            Ok(CharacterSelectButton::Character(id)) if profile.character_lock(*id).is_none() => {
+                selected.0 = *id;
+                next_state.set(GameState::MainMenu);
+            }
+            Ok(CharacterSelectButton::Back) => next_state.set(GameState::MainMenu),
+            _ => {}
+        }
+    }
+}
+
+fn apply_passive_bonus(
+    mut level_ups: EventReader<LevelUpEvent>,
+    selected: Res<SelectedCharacter>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+    mut health_q: Query<&mut Health, With<Player>>,
+) {
+    for _ in level_ups.read() {
+        let Ok(mut health) = health_q.get_single_mut() else {
+            return;
+        };
+        match selected.0.definition().passive {
+            PassiveBonus::MoveSpeed(s) => player_stats.move_speed += s,
+            PassiveBonus::ProjectileSpeed(s) => weapon_stats.projectile_speed += s,
+            PassiveBonus::MaxHealth(h) => {
+                health.max += h;
+                health.current += h;
+            }
+            PassiveBonus::Heal(h) => health.current = (health.current + h).min(health.max),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Treasure chests, dropped by elites and bosses. Opening one pauses the run and reveals
+//! its rewards one at a time: an evolution if one is ready, otherwise 1, 3 or 5 extra
+//! levels spread over what the player already owns. Luck makes the bigger chests likelier.
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    evolution,
+    menu::{self, MenuActivated},
+    pickup::{PickupCollected, PickupKind},
+    state::{PauseState, RunRng},
+    upgrade::{UpgradeSlot, UpgradeTargets},
+};
+
+/// Seconds between rewards appearing.
+const REVEAL_INTERVAL: f32 = 0.6;
+
+/// Rewards of the chest being opened, as lines of text.
+#[derive(Resource, Default)]
+struct ChestRewards {
+    lines: Vec<String>,
+    revealed: usize,
+}
+
+#[derive(Component)]
+struct ChestRewardText;
+
+#[derive(Component)]
+struct ChestRoot;
+
+#[derive(Component)]
+struct ChestContinueButton;
+
+/// Real time, since virtual time is paused while the chest is open.
+#[derive(Resource)]
+struct RevealTimer(Timer);
+
+pub struct ChestPlugin;
+
+impl Plugin for ChestPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ChestRewards::default())
+            .insert_resource(RevealTimer(Timer::from_seconds(
+                REVEAL_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .add_systems(OnEnter(PauseState::Chest), spawn_chest_screen)
+            .add_systems(OnExit(PauseState::Chest), clear_rewards)
+            .add_systems(Update, open_chests.run_if(in_state(PauseState::Running)))
+            .add_systems(
+                Update,
+                (reveal_rewards, handle_continue).run_if(in_state(PauseState::Chest)),
+            );
+    }
+}
+
+/// Number of levels in a chest: 1 usually, 3 or 5 with luck.
+fn roll_levels(luck: f32, rng: &mut impl Rng) -> u32 {
+    let weights = [(1, 70.0), (3, 25.0 * luck), (5, 5.0 * luck * luck)];
+    weights
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map(|(levels, _)| *levels)
+        .unwrap_or(1)
+}
+
+fn open_chests(
+    mut collected: EventReader<PickupCollected>,
+    mut targets: UpgradeTargets,
+    mut rewards: ResMut<ChestRewards>,
+    mut run_rng: ResMut<RunRng>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    for ev in collected.read() {
+        if ev.kind != PickupKind::Chest {
+            continue;
+        }
+
+        if let Some(evolution) = evolution::ready(&targets.levels, &targets.weapon_stats) {
+            let def = evolution.definition();
+            targets.weapon_stats.evolved.push(def.weapon);
+            rewards.lines.push(format!("Evolution! {}", def.name));
+            next_state.set(PauseState::Chest);
+            continue;
+        }
+
+        let levels = roll_levels(targets.player_stats.luck, &mut run_rng.rng);
+        for _ in 0..levels {
+            // Other weapons are skipped so the chest never switches the active shot
+            let current_shot = targets.weapon_stats.current_shot_type;
+            let owned = targets
+                .levels
+                .0
+                .iter()
+                .map(|(slot, _)| *slot)
+                .filter(|slot| match slot {
+                    UpgradeSlot::Weapon(kind) => *kind == current_shot,
+                    _ => true,
+                })
+                .filter_map(|slot| slot.level_up_effect())
+                .collect::<Vec<_>>();
+            let Some(effect) = owned.choose(&mut run_rng.rng) else {
+                break;
+            };
+            targets.apply_upgrade(effect);
+            rewards.lines.push(format!(
+                "{} (Lv {})",
+                effect.label(),
+                targets.levels.level(effect.slot())
+            ));
+        }
+        next_state.set(PauseState::Chest);
+    }
+}
+
+fn spawn_chest_screen(mut commands: Commands, mut timer: ResMut<RevealTimer>) {
+    timer.0.reset();
+    commands
+        .spawn((menu::root_node(), ChestRoot, StateScoped(PauseState::Chest)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Treasure Chest!");
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(1.0, 0.85, 0.3),
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center)
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                }),
+                ChestRewardText,
+            ));
+        });
+}
+
+fn reveal_rewards(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut timer: ResMut<RevealTimer>,
+    mut rewards: ResMut<ChestRewards>,
+    mut text_q: Query<&mut Text, With<ChestRewardText>>,
+    root_q: Query<Entity, With<ChestRoot>>,
+) {
+    if rewards.revealed >= rewards.lines.len() || !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    rewards.revealed += 1;
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = rewards.lines[..rewards.revealed].join("\n");
+    }
+
+    if rewards.revealed == rewards.lines.len() {
+        for root in root_q.iter() {
+            commands.entity(root).with_children(|parent| {
+                menu::spawn_button(parent, 0, "Continue", ChestContinueButton);
+            });
+        }
+    }
+}
+
+fn handle_continue(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<(), With<ChestContinueButton>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    for ev in activated.read() {
+        if button_q.contains(ev.0) {
+            next_state.set(PauseState::Running);
+        }
+    }
+}
+
+fn clear_rewards(mut rewards: ResMut<ChestRewards>) {
+    *rewards = ChestRewards::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn count_fives(luck: f32) -> usize {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..10_000).filter(|_| roll_levels(luck, &mut rng) == 5).count()
+    }
+
+    #[test]
+    fn chests_hold_one_three_or_five_levels() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1_000 {
+            assert!(matches!(roll_levels(1.0, &mut rng), 1 | 3 | 5));
+        }
+    }
+
+    #[test]
+    fn no_luck_means_single_levels() {
+        let mut rng = StdRng::seed_from_u64(2);
+        assert!((0..1_000).all(|_| roll_levels(0.0, &mut rng) == 1));
+    }
+
+    #[test]
+    fn luck_makes_big_chests_likelier() {
+        assert!(count_fives(2.0) > count_fives(1.0) * 2);
+    }
+}
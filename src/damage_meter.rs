@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    enemy::EnemyKilledEvent,
+    health::DamageApplied,
+    input::{Action, ActionState},
+    state::{GameState, PauseState, RunTimer},
+    weapon::WeaponKind,
+};
+
+/// Window the live DPS figure is averaged over.
+const DPS_WINDOW: f32 = 10.0;
+
+#[derive(Default)]
+pub struct WeaponTally {
+    pub total_damage: f32,
+    pub kills: u32,
+    /// (run time, damage) of hits inside the DPS window.
+    recent: VecDeque<(f32, f32)>,
+}
+
+impl WeaponTally {
+    /// Damage per second over the last `DPS_WINDOW` seconds of the run.
+    pub fn dps(&self, now: f32) -> f32 {
+        let recent = self
+            .recent
+            .iter()
+            .filter(|(t, _)| now - t <= DPS_WINDOW)
+            .map(|(_, amount)| amount)
+            .sum::<f32>();
+        recent / now.clamp(f32::EPSILON, DPS_WINDOW)
+    }
+}
+
+/// Damage and kills per weapon this run, in the order each weapon first dealt damage.
+#[derive(Resource, Default)]
+pub struct DamageMeter(pub Vec<(WeaponKind, WeaponTally)>);
+
+impl DamageMeter {
+    fn tally(&mut self, weapon: WeaponKind) -> &mut WeaponTally {
+        let index = match self.0.iter().position(|(w, _)| *w == weapon) {
+            Some(index) => index,
+            None => {
+                self.0.push((weapon, WeaponTally::default()));
+                self.0.len() - 1
+            }
+        };
+        &mut self.0[index].1
+    }
+}
+
+/// Whether the in-run overlay is shown. Kept across runs.
+#[derive(Resource, Default)]
+struct ShowDamageMeter(bool);
+
+#[derive(Component)]
+struct DamageMeterOverlay;
+
+#[derive(Component)]
+struct DamageMeterText;
+
+pub struct DamageMeterPlugin;
+
+impl Plugin for DamageMeterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DamageMeter::default())
+            .insert_resource(ShowDamageMeter::default())
+            .add_systems(OnEnter(GameState::Playing), spawn_overlay)
+            .add_systems(
+                Update,
+                (record_damage, record_kills, toggle_overlay, update_overlay)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn record_damage(
+    mut applied: EventReader<DamageApplied>,
+    mut meter: ResMut<DamageMeter>,
+    run_timer: Res<RunTimer>,
+) {
+    let now = run_timer.0.elapsed_secs();
+    for ev in applied.read() {
+        let Some(weapon) = ev.weapon else {
+            continue;
+        };
+        let tally = meter.tally(weapon);
+        tally.total_damage += ev.amount;
+        tally.recent.push_back((now, ev.amount));
+    }
+
+    for (_, tally) in meter.0.iter_mut() {
+        while tally.recent.front().is_some_and(|(t, _)| now - t > DPS_WINDOW) {
+            tally.recent.pop_front();
+        }
+    }
+}
+
+fn record_kills(mut kills: EventReader<EnemyKilledEvent>, mut meter: ResMut<DamageMeter>) {
+    for ev in kills.read() {
+        if let Some(weapon) = ev.killed_by {
+            meter.tally(weapon).kills += 1;
+        }
+    }
+}
+
+fn spawn_overlay(mut commands: Commands, show: Res<ShowDamageMeter>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(90.0),
+                    right: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                visibility: if show.0 {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                ..default()
+            },
+            DamageMeterOverlay,
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                DamageMeterText,
+            ));
+        });
+}
+
+fn toggle_overlay(
+    actions: Res<ActionState>,
+    mut show: ResMut<ShowDamageMeter>,
+    mut overlay_q: Query<&mut Visibility, With<DamageMeterOverlay>>,
+) {
+    if !actions.just_pressed(Action::ToggleStats) {
+        return;
+    }
+    show.0 = !show.0;
+    for mut visibility in overlay_q.iter_mut() {
+        *visibility = if show.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_overlay(
+    show: Res<ShowDamageMeter>,
+    meter: Res<DamageMeter>,
+    run_timer: Res<RunTimer>,
+    mut text_q: Query<&mut Text, With<DamageMeterText>>,
+) {
+    if !show.0 {
+        return;
+    }
+    let now = run_timer.0.elapsed_secs();
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = breakdown_lines(&meter, |tally| tally.dps(now)).join("\n");
+    }
+}
+
+/// One line per weapon: name, total damage, kills and the given DPS figure.
+pub fn breakdown_lines(meter: &DamageMeter, dps: impl Fn(&WeaponTally) -> f32) -> Vec<String> {
+    if meter.0.is_empty() {
+        return vec!["No damage dealt".to_string()];
+    }
+    meter
+        .0
+        .iter()
+        .map(|(weapon, tally)| {
+            format!(
+                "{:<14} {:>7.0} dmg  {:>4} kills  {:>6.1} dps",
+                weapon.name(),
+                tally.total_damage,
+                tally.kills,
+                dps(tally)
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,179 @@
+use bevy::{color::palettes::css, prelude::*, utils::HashMap};
+
+use crate::{
+    health::{DamageApplied, DamageType},
+    player::Player,
+    state::{GameState, PauseState},
+};
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DamageNumberSettings {
+    pub enabled: bool,
+    /// Oldest numbers are removed once this many are on screen.
+    pub max_numbers: usize,
+}
+
+impl Default for DamageNumberSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_numbers: 60,
+        }
+    }
+}
+
+/// Hits on the same target within this window are summed into one number.
+const AGGREGATE_WINDOW: f32 = 0.2;
+const NUMBER_LIFETIME: f32 = 0.8;
+
+struct PendingDamage {
+    amount: f32,
+    position: Vec3,
+    color: Color,
+    crit: bool,
+    window: Timer,
+}
+
+/// Damage accumulated per target that hasn't been shown yet.
+#[derive(Resource, Default)]
+struct PendingDamageNumbers(HashMap<Entity, PendingDamage>);
+
+#[derive(Component)]
+pub struct DamageNumber {
+    pub lifetime: Timer,
+    pub velocity: Vec2,
+}
+
+pub struct DamageNumberPlugin;
+
+impl Plugin for DamageNumberPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DamageNumberSettings::default())
+            .insert_resource(PendingDamageNumbers::default())
+            .register_type::<DamageNumberSettings>()
+            .add_systems(OnExit(GameState::Playing), clear_pending)
+            .add_systems(
+                Update,
+                (collect_damage, spawn_damage_numbers, animate_damage_numbers)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn collect_damage(
+    mut applied: EventReader<DamageApplied>,
+    settings: Res<DamageNumberSettings>,
+    mut pending: ResMut<PendingDamageNumbers>,
+    player_q: Query<(), With<Player>>,
+) {
+    for ev in applied.read() {
+        // Fully resisted hits don't get a "0"
+        if !settings.enabled || ev.amount <= 0.0 {
+            continue;
+        }
+        let is_player = player_q.contains(ev.entity);
+
+        let color = match ev.damage_type {
+            _ if is_player => css::RED.into(),
+            DamageType::Physical => Color::WHITE,
+            DamageType::Fire => css::ORANGE.into(),
+            DamageType::Ice => css::LIGHT_BLUE.into(),
+            DamageType::Poison => css::LIME.into(),
+        };
+        let entry = pending.0.entry(ev.entity).or_insert_with(|| PendingDamage {
+            amount: 0.0,
+            position: ev.position,
+            color,
+            crit: false,
+            window: Timer::from_seconds(AGGREGATE_WINDOW, TimerMode::Once),
+        });
+        entry.amount += ev.amount;
+        entry.crit |= ev.crit;
+        entry.position = ev.position;
+    }
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<DamageNumberSettings>,
+    mut pending: ResMut<PendingDamageNumbers>,
+    existing_q: Query<(Entity, &DamageNumber)>,
+) {
+    let mut ready = Vec::new();
+    pending.0.retain(|_, p| {
+        p.window.tick(time.delta());
+        if p.window.finished() {
+            ready.push((p.amount, p.position, p.color, p.crit));
+            false
+        } else {
+            true
+        }
+    });
+    if ready.is_empty() {
+        return;
+    }
+
+    // Make room by dropping the oldest numbers first.
+    let mut existing = existing_q
+        .iter()
+        .map(|(e, n)| (e, n.lifetime.elapsed_secs()))
+        .collect::<Vec<_>>();
+    let overflow = (existing.len() + ready.len()).saturating_sub(settings.max_numbers);
+    if overflow > 0 {
+        existing.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (e, _) in existing.into_iter().take(overflow) {
+            commands.entity(e).despawn();
+        }
+    }
+
+    for (amount, position, color, crit) in ready.into_iter().take(settings.max_numbers) {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    if crit {
+                        format!("{:.0}!", amount)
+                    } else {
+                        format!("{:.0}", amount)
+                    },
+                    TextStyle {
+                        font_size: if crit { 26.0 } else { 18.0 },
+                        color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(position + Vec3::new(0.0, 30.0, 20.0)),
+                ..default()
+            },
+            DamageNumber {
+                lifetime: Timer::from_seconds(NUMBER_LIFETIME, TimerMode::Once),
+                velocity: Vec2::new(0.0, 40.0),
+            },
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+fn animate_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut DamageNumber, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut number, mut transform, mut text) in q.iter_mut() {
+        number.lifetime.tick(time.delta());
+        transform.translation += (number.velocity * time.delta_seconds()).extend(0.0);
+        let alpha = number.lifetime.fraction_remaining();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+        if number.lifetime.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn clear_pending(mut pending: ResMut<PendingDamageNumbers>) {
+    pending.0.clear();
+}
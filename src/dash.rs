@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use crate::{
+    enemy::Enemy,
+    health::{DamageEvent, DamageType, Invulnerable},
+    input::{Action, ActionState},
+    obstacle::{self, Obstacle},
+    player::Player,
+    state::{GameState, PauseState},
+    weapon::WeaponKind,
+};
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DashStats {
+    pub max_charges: u32,
+    pub cooldown: f32, // Seconds to recharge one charge
+    pub distance: f32,
+    pub duration: f32,
+    pub trail_damage: f32, // 0 disables the damage trail
+}
+
+impl Default for DashStats {
+    fn default() -> Self {
+        Self {
+            max_charges: 1,
+            cooldown: 1.5,
+            distance: 120.0,
+            duration: 0.15,
+            trail_damage: 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Dash {
+    pub charges: u32,
+    pub recharge: Timer,
+    pub active: Option<Timer>,
+    pub direction: Vec2,
+    pub facing: Vec2, // Last movement direction, used when dashing from standstill
+    pub last_trail: Vec2,
+}
+
+impl Dash {
+    pub fn new(stats: &DashStats) -> Self {
+        Self {
+            charges: stats.max_charges,
+            recharge: Timer::from_seconds(stats.cooldown, TimerMode::Once),
+            active: None,
+            direction: Vec2::ZERO,
+            facing: Vec2::X,
+            last_trail: Vec2::ZERO,
+        }
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        self.active.is_some()
+    }
+}
+
+#[derive(Component)]
+pub struct DashTrail {
+    pub damage: f32,
+    pub lifetime: Timer,
+    pub hit: Vec<Entity>, // Each enemy is hit at most once per segment
+}
+
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DashStats::default())
+            .register_type::<DashStats>()
+            .add_systems(
+                Update,
+                (
+                    start_dash,
+                    dash_movement,
+                    recharge_dash,
+                    dash_trail_damage,
+                    dash_trail_cleanup,
+                )
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn start_dash(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    stats: Res<DashStats>,
+    mut q: Query<(Entity, &mut Dash, &Transform), With<Player>>,
+) {
+    let (player_e, mut dash, transform) = q.single_mut();
+
+    if actions.move_axis != Vec2::ZERO {
+        dash.facing = actions.move_axis.normalize();
+    }
+
+    if !actions.just_pressed(Action::Dash) || dash.is_dashing() || dash.charges == 0 {
+        return;
+    }
+
+    dash.charges -= 1;
+    dash.direction = dash.facing;
+    dash.active = Some(Timer::from_seconds(stats.duration, TimerMode::Once));
+    dash.last_trail = transform.translation.truncate();
+    commands.entity(player_e).insert(Invulnerable);
+}
+
+fn dash_movement(
+    mut commands: Commands,
+    time: Res<Time>,
+    stats: Res<DashStats>,
+    mut q: Query<(Entity, &mut Dash, &mut Transform), With<Player>>,
+    obstacle_q: Query<&Obstacle>,
+) {
+    let (player_e, mut dash, mut transform) = q.single_mut();
+    let direction = dash.direction;
+
+    let Some(active) = dash.active.as_mut() else {
+        return;
+    };
+    active.tick(time.delta());
+
+    let speed = stats.distance / stats.duration;
+    let target = transform.translation.truncate() + direction * speed * time.delta_seconds();
+    let pos = obstacle::resolve(target, obstacle::BODY_RADIUS, &obstacle_q);
+    transform.translation = pos.extend(transform.translation.z);
+
+    if stats.trail_damage > 0.0 {
+        let pos = transform.translation.truncate();
+        if pos.distance(dash.last_trail) >= 20.0 {
+            dash.last_trail = pos;
+            commands.spawn((
+                SpriteBundle {
+                    transform: Transform::from_translation(pos.extend(-1.0)),
+                    sprite: Sprite {
+                        color: Color::srgba(0.6, 0.3, 1.0, 0.6),
+                        custom_size: Some(Vec2::splat(24.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                DashTrail {
+                    damage: stats.trail_damage,
+                    lifetime: Timer::from_seconds(1.0, TimerMode::Once),
+                    hit: Vec::new(),
+                },
+                Name::new("Dash Trail"),
+                StateScoped(GameState::Playing),
+            ));
+        }
+    }
+
+    if dash.active.as_ref().is_some_and(|t| t.finished()) {
+        dash.active = None;
+        commands.entity(player_e).remove::<Invulnerable>();
+    }
+}
+
+fn recharge_dash(time: Res<Time>, stats: Res<DashStats>, mut q: Query<&mut Dash, With<Player>>) {
+    let mut dash = q.single_mut();
+
+    if dash.charges >= stats.max_charges {
+        dash.recharge.reset();
+        return;
+    }
+
+    dash.recharge.set_duration(std::time::Duration::from_secs_f32(stats.cooldown));
+    dash.recharge.tick(time.delta());
+    if dash.recharge.finished() {
+        dash.charges += 1;
+        dash.recharge.reset();
+    }
+}
+
+fn dash_trail_damage(
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut trail_q: Query<(Entity, &Transform, &mut DashTrail)>,
+    enemy_q: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    for (trail_e, trail_t, mut trail) in trail_q.iter_mut() {
+        for (enemy_e, enemy_t) in enemy_q.iter() {
+            if trail.hit.contains(&enemy_e) {
+                continue;
+            }
+            if trail_t.translation.truncate().distance(enemy_t.translation.truncate()) < 20.0 {
+                damage_writer.send(DamageEvent {
+                    entity: enemy_e,
+                    amount: trail.damage,
+                    source: Some(trail_e),
+                    damage_type: DamageType::Physical,
+                    crit: false,
+                    knockback: Vec2::ZERO,
+                    weapon: Some(WeaponKind::DashTrail),
+                });
+                trail.hit.push(enemy_e);
+            }
+        }
+    }
+}
+
+fn dash_trail_cleanup(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut DashTrail, &mut Sprite)>,
+) {
+    for (entity, mut trail, mut sprite) in q.iter_mut() {
+        trail.lifetime.tick(time.delta());
+        sprite.color.set_alpha(0.6 * trail.lifetime.fraction_remaining());
+        if trail.lifetime.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
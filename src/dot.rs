@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use crate::{
+    health::{DamageEvent, DamageType},
+    state::PauseState,
+    weapon::WeaponKind,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect)]
+pub enum DotKind {
+    Burning,
+    Poison,
+}
+
+/// What happens when a DoT is applied to a target that already has it.
+pub enum Stacking {
+    /// Restart the duration, keeping the strongest tick damage.
+    Refresh,
+    /// Restart the duration and add a stack, each one dealing full tick damage.
+    Intensity { max_stacks: u32 },
+}
+
+pub struct DotDefinition {
+    pub damage_type: DamageType,
+    pub tick_interval: f32,
+    pub duration: f32,
+    pub stacking: Stacking,
+}
+
+impl DotKind {
+    pub fn definition(&self) -> DotDefinition {
+        match self {
+            DotKind::Burning => DotDefinition {
+                damage_type: DamageType::Fire,
+                tick_interval: 0.5,
+                duration: 3.0,
+                stacking: Stacking::Refresh,
+            },
+            DotKind::Poison => DotDefinition {
+                damage_type: DamageType::Poison,
+                tick_interval: 1.0,
+                duration: 4.0,
+                stacking: Stacking::Intensity { max_stacks: 5 },
+            },
+        }
+    }
+}
+
+pub struct Dot {
+    pub kind: DotKind,
+    pub damage_per_tick: f32,
+    pub stacks: u32,
+    /// Whatever applied the DoT; ticks are reported as coming from it.
+    pub source: Option<Entity>,
+    pub weapon: Option<WeaponKind>,
+    tick: Timer,
+    remaining: Timer,
+}
+
+/// Damage-over-time effects currently on an entity. Removed along with it on death.
+#[derive(Component, Default)]
+pub struct ActiveDots(pub Vec<Dot>);
+
+#[derive(Event)]
+pub struct ApplyDotEvent {
+    pub entity: Entity,
+    pub kind: DotKind,
+    pub damage_per_tick: f32,
+    pub source: Option<Entity>,
+    pub weapon: Option<WeaponKind>,
+}
+
+pub struct DotPlugin;
+
+impl Plugin for DotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyDotEvent>().add_systems(
+            Update,
+            (apply_dots, tick_dots)
+                .chain()
+                .run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+fn apply_dots(mut events: EventReader<ApplyDotEvent>, mut target_q: Query<&mut ActiveDots>) {
+    for ev in events.read() {
+        let Ok(mut dots) = target_q.get_mut(ev.entity) else {
+            continue;
+        };
+        let def = ev.kind.definition();
+
+        match dots.0.iter_mut().find(|d| d.kind == ev.kind) {
+            Some(dot) => {
+                dot.remaining.reset();
+                dot.source = ev.source;
+                dot.weapon = ev.weapon;
+                match def.stacking {
+                    Stacking::Refresh => {
+                        dot.damage_per_tick = dot.damage_per_tick.max(ev.damage_per_tick);
+                    }
+                    Stacking::Intensity { max_stacks } => {
+                        dot.damage_per_tick = ev.damage_per_tick;
+                        dot.stacks = (dot.stacks + 1).min(max_stacks);
+                    }
+                }
+            }
+            None => dots.0.push(Dot {
+                kind: ev.kind,
+                damage_per_tick: ev.damage_per_tick,
+                stacks: 1,
+                source: ev.source,
+                weapon: ev.weapon,
+                tick: Timer::from_seconds(def.tick_interval, TimerMode::Repeating),
+                remaining: Timer::from_seconds(def.duration, TimerMode::Once),
+            }),
+        }
+    }
+}
+
+fn tick_dots(
+    time: Res<Time>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut target_q: Query<(Entity, &mut ActiveDots)>,
+) {
+    for (entity, mut dots) in target_q.iter_mut() {
+        for dot in dots.0.iter_mut() {
+            dot.tick.tick(time.delta());
+            dot.remaining.tick(time.delta());
+
+            for _ in 0..dot.tick.times_finished_this_tick() {
+                damage_writer.send(DamageEvent {
+                    entity,
+                    amount: dot.damage_per_tick * dot.stacks as f32,
+                    source: dot.source,
+                    damage_type: dot.kind.definition().damage_type,
+                    crit: false,
+                    knockback: Vec2::ZERO,
+                    weapon: dot.weapon,
+                });
+            }
+        }
+        dots.0.retain(|dot| !dot.remaining.finished());
+    }
+}
@@ -1,11 +1,12 @@
 use std::time::Duration;
 
-use crate::{health::{DamageCooldown, DamageEvent, Health}, player::Player};
+use crate::{dot::ActiveDots, flow_field::FlowField, health::{DamageCooldown, DamageEvent, DamageType, Health, Invulnerable, Knockback, Resistances}, obstacle::{self, Obstacle}, player::Player, run_setup::RunSetup, stage::StageBounds, state::{GameState, RunRng, RunTimer}, weapon::WeaponKind};
 use bevy::prelude::*;
-use rand::Rng;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 
-#[derive(Reflect)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum EnemyType {
     Skeleton,
     Orc,
@@ -20,6 +21,9 @@ pub struct EnemyDefinition {
     pub rows: u32,
     pub scale: f32,
     pub speed: f32,
+    pub resistances: Resistances,
+    /// Value of the XP gem dropped on death.
+    pub xp: u32,
 }
 
 impl EnemyType {
@@ -33,6 +37,15 @@ impl EnemyType {
                 rows: 7,
                 scale: 1.5,
                 speed: 50.0,
+                // Brittle bones: shrug off blades, shatter in the cold, crumble to fire
+                resistances: Resistances {
+                    physical: 0.75,
+                    fire: 1.5,
+                    ice: 1.25,
+                    // Nothing left to poison
+                    poison: 0.0,
+                },
+                xp: 1,
             },
             EnemyType::Orc => EnemyDefinition {
                 name: "Orc",
@@ -42,6 +55,13 @@ impl EnemyType {
                 rows: 7,
                 scale: 1.5,
                 speed: 100.0,
+                resistances: Resistances {
+                    physical: 0.8,
+                    fire: 1.0,
+                    ice: 1.5,
+                    poison: 1.0,
+                },
+                xp: 2,
             },
             EnemyType::Werewolf => EnemyDefinition {
                 name: "Werewolf",
@@ -51,6 +71,65 @@ impl EnemyType {
                 rows: 7,
                 scale: 1.5,
                 speed: 150.0,
+                resistances: Resistances {
+                    physical: 1.0,
+                    fire: 1.5,
+                    ice: 0.5,
+                    poison: 0.75,
+                },
+                xp: 3,
+            },
+        }
+    }
+}
+
+/// Sent once per enemy when it dies, after its drops have been spawned.
+#[derive(Event)]
+pub struct EnemyKilledEvent {
+    pub enemy_type: EnemyType,
+    pub killed_by: Option<WeaponKind>,
+}
+
+/// Elites and bosses are tougher versions of an enemy type that drop a chest.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum EnemyRank {
+    Normal,
+    Elite,
+    Boss,
+}
+
+pub struct EnemyRankDefinition {
+    /// Put in front of the enemy's name.
+    pub prefix: &'static str,
+    pub health: f32,
+    pub scale: f32,
+    pub tint: Color,
+    pub drops_chest: bool,
+}
+
+impl EnemyRank {
+    pub fn definition(&self) -> EnemyRankDefinition {
+        match self {
+            EnemyRank::Normal => EnemyRankDefinition {
+                prefix: "",
+                health: 1.0,
+                scale: 1.0,
+                tint: Color::WHITE,
+                drops_chest: false,
+            },
+            EnemyRank::Elite => EnemyRankDefinition {
+                prefix: "Elite ",
+                health: 5.0,
+                scale: 1.4,
+                tint: Color::srgb(1.0, 0.85, 0.5),
+                drops_chest: true,
+            },
+            EnemyRank::Boss => EnemyRankDefinition {
+                prefix: "Boss ",
+                health: 25.0,
+                scale: 2.0,
+                tint: Color::srgb(1.0, 0.55, 0.55),
+                drops_chest: true,
             },
         }
     }
@@ -60,8 +139,29 @@ impl EnemyType {
 pub struct Enemy {
     pub speed: f32,
     pub enemy_type: EnemyType,
+    pub rank: EnemyRank,
+}
+
+/// While running, enemies can neither move nor hurt the player.
+#[derive(Resource, Default)]
+pub struct EnemyFreeze {
+    remaining: f32,
+}
+
+impl EnemyFreeze {
+    pub fn start(&mut self, duration: f32) {
+        self.remaining = self.remaining.max(duration);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
 }
 
+/// Index of the last wave whose boss has been spawned this run.
+#[derive(Resource, Default)]
+pub struct BossWave(Option<usize>);
+
 #[derive(Resource, Reflect)]
 pub struct EnemySpawnTimer(Timer);
 
@@ -72,25 +172,78 @@ impl Default for EnemySpawnTimer {
 }
 
 pub fn enemy_movement(
-    mut enemies: Query<(&mut Transform, &Enemy),  Without<Player>>,
+    mut enemies: Query<(&mut Transform, &Enemy, &mut Knockback),  Without<Player>>,
     player: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    obstacle_q: Query<&Obstacle>,
+    flow_field: Res<FlowField>,
+    freeze: Res<EnemyFreeze>,
     time: Res<Time>,
 ) {
+    if freeze.is_active() {
+        return;
+    }
     let player_transform = player.single();
-    for (mut transform, enemy) in enemies.iter_mut() {
-        let dir = (player_transform.translation - transform.translation)
-            .truncate()
-            .normalize_or_zero();
-        transform.translation += (dir * enemy.speed * time.delta_seconds()).extend(0.0);
+    for (mut transform, enemy, mut knockback) in enemies.iter_mut() {
+        let dir = flow_field
+            .direction_at(transform.translation.truncate())
+            .unwrap_or_else(|| {
+                (player_transform.translation - transform.translation)
+                    .truncate()
+                    .normalize_or_zero()
+            });
+        let velocity = dir * enemy.speed + knockback.0;
+        let target = transform.translation.truncate() + velocity * time.delta_seconds();
+        let pos = obstacle::resolve(target, obstacle::BODY_RADIUS, &obstacle_q);
+        transform.translation = pos.extend(transform.translation.z);
+
+        // Knockback bleeds off quickly so enemies resume the chase
+        knockback.0 *= (1.0 - 8.0 * time.delta_seconds()).max(0.0);
     }
 }
 
-pub fn difficulty_scaling(mut timer: ResMut<EnemySpawnTimer>, time: Res<Time>) {
-    let elapsed = time.elapsed_seconds();
-    let new_interval = (0.25 + (100.0 / (elapsed + 100.0))).max(0.1);
+pub fn tick_enemy_freeze(mut freeze: ResMut<EnemyFreeze>, time: Res<Time>) {
+    if freeze.is_active() {
+        freeze.remaining -= time.delta_seconds();
+    }
+}
+
+/// Colour of an enemy of `rank`, accounting for any freeze.
+fn enemy_tint(rank: EnemyRank, frozen: bool) -> Color {
+    if frozen {
+        Color::srgb(0.6, 0.85, 1.0)
+    } else {
+        rank.definition().tint
+    }
+}
+
+/// The only place enemy tints are set after spawning: every enemy when a freeze starts or
+/// ends, and enemies that spawned since the last frame.
+pub fn tint_enemies(
+    freeze: Res<EnemyFreeze>,
+    mut was_frozen: Local<bool>,
+    mut enemy_q: Query<(Ref<Enemy>, &mut Sprite)>,
+) {
+    let frozen = freeze.is_active();
+    let changed = frozen != *was_frozen;
+    *was_frozen = frozen;
+    for (enemy, mut sprite) in enemy_q.iter_mut() {
+        if changed || enemy.is_added() {
+            sprite.color = enemy_tint(enemy.rank, frozen);
+        }
+    }
+}
+
+pub fn difficulty_scaling(
+    mut timer: ResMut<EnemySpawnTimer>,
+    run_timer: Res<RunTimer>,
+    setup: Res<RunSetup>,
+) {
+    let wave = setup.stage.definition().wave_at(run_timer.0.elapsed_secs());
+    let new_interval = wave.spawn_interval * setup.multiplier(|m| m.spawn_interval);
     timer.0.set_duration(Duration::from_secs_f32(new_interval));
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn enemy_spawner(
     mut commands: Commands,
     time: Res<Time>,
@@ -98,70 +251,121 @@ pub fn enemy_spawner(
     player_q: Query<&Transform, With<Player>>,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut run_rng: ResMut<RunRng>,
+    setup: Res<RunSetup>,
+    run_timer: Res<RunTimer>,
+    freeze: Res<EnemyFreeze>,
+    mut boss_wave: ResMut<BossWave>,
 ) {
     timer.0.tick(time.delta());
 
-    if timer.0.finished() {
-        let player_t = player_q.single();
-        let mut rng = rand::thread_rng();
-
-        // random enemy type
-        let enemy_type = match rng.gen_range(0..3) {
-            0 => EnemyType::Skeleton,
-            1 => EnemyType::Orc,
-            _ => EnemyType::Werewolf,
-        };
-
-        let def = enemy_type.definition();
+    let player_t = player_q.single();
+    let rng = &mut run_rng.rng;
+    let stage = setup.stage.definition();
+    let wave_index = stage.wave_index_at(run_timer.0.elapsed_secs());
+    let wave = &stage.waves[wave_index];
 
-        // Spawn enemies in a ring around the player
-        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    // Spawn enemies in a ring around the player. In an arena, angles whose point falls
+    // outside the walls are rerolled, so enemies never end up clamped onto the player.
+    let player_pos = player_t.translation.truncate();
+    let spawn_point = |rng: &mut StdRng| {
         let radius = rng.gen_range(300.0..400.0);
-        let spawn_x = player_t.translation.x + radius * angle.cos();
-        let spawn_y = player_t.translation.y + radius * angle.sin();
-
-        let texture = asset_server.load(def.texture_path);
-
+        let StageBounds::Arena { half_size } = stage.bounds else {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            return player_pos + Vec2::from_angle(angle) * radius;
+        };
+        let inner = half_size - obstacle::BODY_RADIUS;
+        for _ in 0..16 {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let spawn = player_pos + Vec2::from_angle(angle) * radius;
+            if spawn.abs().cmple(inner).all() {
+                return spawn;
+            }
+        }
+        // The arena is much wider than the ring, so towards its centre is always inside
+        player_pos + (-player_pos).normalize_or(Vec2::X) * radius
+    };
+    let mut spawn = |enemy_type: EnemyType, rank: EnemyRank, pos: Vec2| {
+        let def = enemy_type.definition();
+        let rank_def = rank.definition();
         let layout = TextureAtlasLayout::from_grid(def.frame_size, def.columns, def.rows, None, None);
-        let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
         commands.spawn((
             SpriteBundle {
-                transform: Transform::from_xyz(spawn_x, spawn_y, 0.0).with_scale(Vec3::splat(def.scale)),
-                texture: texture.clone(),
+                transform: Transform::from_translation(pos.extend(0.0))
+                    .with_scale(Vec3::splat(def.scale * rank_def.scale)),
+                texture: asset_server.load(def.texture_path),
+                sprite: Sprite {
+                    color: enemy_tint(rank, freeze.is_active()),
+                    ..default()
+                },
                 ..default()
             },
             TextureAtlas {
-                layout: texture_atlas_layout,
+                layout: texture_atlas_layouts.add(layout),
                 index: 0,
             },
             Enemy {
-                speed: def.speed,
+                speed: def.speed * setup.multiplier(|m| m.enemy_speed),
                 enemy_type,
+                rank,
             },
-            Health::new(100.0),
-            Name::new(def.name),
+            Health::new(100.0 * rank_def.health * setup.multiplier(|m| m.enemy_health)),
+            def.resistances,
+            Knockback::default(),
+            ActiveDots::default(),
+            Name::new(format!("{}{}", rank_def.prefix, def.name)),
+            StateScoped(GameState::Playing),
         ));
+    };
+
+    // A wave's boss arrives once, on the first frame of the wave
+    if boss_wave.0.is_none_or(|last| wave_index > last) {
+        boss_wave.0 = Some(wave_index);
+        if let Some(boss) = wave.boss {
+            let pos = spawn_point(rng);
+            spawn(boss, EnemyRank::Boss, pos);
+        }
+    }
+
+    if timer.0.finished() {
+        let enemy_type = wave
+            .enemies
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(enemy_type, _)| *enemy_type)
+            .unwrap_or(EnemyType::Skeleton);
+        let rank = if rng.gen_bool(wave.elite_chance as f64) {
+            EnemyRank::Elite
+        } else {
+            EnemyRank::Normal
+        };
+        let pos = spawn_point(rng);
+        spawn(enemy_type, rank, pos);
     }
 }
 
 pub fn enemy_player_collision(
     mut damage_writer: EventWriter<DamageEvent>,
-    mut player_q: Query<(Entity, &Transform, &mut DamageCooldown), With<Player>>,
-    enemy_q: Query<&Transform, With<Enemy>>,
+    mut player_q: Query<(Entity, &Transform, &mut DamageCooldown, Has<Invulnerable>), With<Player>>,
+    enemy_q: Query<(Entity, &Transform), With<Enemy>>,
+    freeze: Res<EnemyFreeze>,
 ) {
-    let (player_e, player_transform, mut cooldown) = player_q.single_mut();
+    let (player_e, player_transform, mut cooldown, invulnerable) = player_q.single_mut();
 
-    if !cooldown.is_ready() {
+    if invulnerable || !cooldown.is_ready() || freeze.is_active() {
         return;
     }
 
-    for enemy_t in enemy_q.iter() {
+    for (enemy_e, enemy_t) in enemy_q.iter() {
         if player_transform.translation.distance(enemy_t.translation) < 20.0 {
-    
             damage_writer.send(DamageEvent {
                 entity: player_e,
                 amount: 10.0,
+                source: Some(enemy_e),
+                damage_type: DamageType::Physical,
+                crit: false,
+                knockback: Vec2::ZERO,
+                weapon: None,
             });
 
             cooldown.timer = Timer::from_seconds(1.0, TimerMode::Once);
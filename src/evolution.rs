@@ -0,0 +1,130 @@
+//! Weapon evolutions. A weapon levelled far enough, paired with the right passive,
+//! evolves into a stronger form the next time the player opens a chest.
+
+use crate::{
+    projectile::ProjectileKind,
+    upgrade::{UpgradeLevels, UpgradeSlot},
+    weapon::WeaponStats,
+};
+
+/// Weapon level needed before it can evolve.
+const EVOLVE_LEVEL: u32 = 3;
+/// Damage multiplier of an evolved weapon.
+pub const EVOLVED_DAMAGE: f32 = 2.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Evolution {
+    ArcaneBarrage,
+    Hellfire,
+    AbsoluteZero,
+    PhantomLance,
+}
+
+pub struct EvolutionDefinition {
+    pub name: &'static str,
+    pub weapon: ProjectileKind,
+    /// Passive that must be owned alongside the weapon.
+    pub passive: UpgradeSlot,
+}
+
+impl Evolution {
+    pub const ALL: [Evolution; 4] = [
+        Evolution::ArcaneBarrage,
+        Evolution::Hellfire,
+        Evolution::AbsoluteZero,
+        Evolution::PhantomLance,
+    ];
+
+    pub fn definition(&self) -> EvolutionDefinition {
+        match self {
+            Evolution::ArcaneBarrage => EvolutionDefinition {
+                name: "Arcane Barrage",
+                weapon: ProjectileKind::Normal,
+                passive: UpgradeSlot::Multishot,
+            },
+            Evolution::Hellfire => EvolutionDefinition {
+                name: "Hellfire",
+                weapon: ProjectileKind::Fireball,
+                passive: UpgradeSlot::Spread,
+            },
+            Evolution::AbsoluteZero => EvolutionDefinition {
+                name: "Absolute Zero",
+                weapon: ProjectileKind::Ice,
+                passive: UpgradeSlot::ShotSpeed,
+            },
+            Evolution::PhantomLance => EvolutionDefinition {
+                name: "Phantom Lance",
+                weapon: ProjectileKind::Piercing,
+                passive: UpgradeSlot::DashCooldown,
+            },
+        }
+    }
+}
+
+/// An evolution the player qualifies for with the weapon they are using, if any.
+pub fn ready(levels: &UpgradeLevels, weapon_stats: &WeaponStats) -> Option<Evolution> {
+    Evolution::ALL.into_iter().find(|evolution| {
+        let def = evolution.definition();
+        def.weapon == weapon_stats.current_shot_type
+            && !weapon_stats.evolved.contains(&def.weapon)
+            && levels.level(UpgradeSlot::Weapon(def.weapon)) >= EVOLVE_LEVEL
+            && levels.level(def.passive) > 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(owned: &[(UpgradeSlot, u32)]) -> UpgradeLevels {
+        UpgradeLevels(owned.to_vec())
+    }
+
+    #[test]
+    fn evolves_a_levelled_weapon_with_its_passive() {
+        let levels = levels(&[
+            (UpgradeSlot::Weapon(ProjectileKind::Normal), EVOLVE_LEVEL),
+            (UpgradeSlot::Multishot, 1),
+        ]);
+        assert_eq!(ready(&levels, &WeaponStats::default()), Some(Evolution::ArcaneBarrage));
+    }
+
+    #[test]
+    fn needs_weapon_level_and_passive() {
+        let stats = WeaponStats::default();
+        let too_low = levels(&[
+            (UpgradeSlot::Weapon(ProjectileKind::Normal), EVOLVE_LEVEL - 1),
+            (UpgradeSlot::Multishot, 1),
+        ]);
+        assert_eq!(ready(&too_low, &stats), None);
+        let no_passive = levels(&[(UpgradeSlot::Weapon(ProjectileKind::Normal), EVOLVE_LEVEL)]);
+        assert_eq!(ready(&no_passive, &stats), None);
+    }
+
+    #[test]
+    fn only_the_current_weapon_evolves() {
+        let levels = levels(&[
+            (UpgradeSlot::Weapon(ProjectileKind::Fireball), EVOLVE_LEVEL),
+            (UpgradeSlot::Spread, 1),
+        ]);
+        assert_eq!(ready(&levels, &WeaponStats::default()), None);
+        let stats = WeaponStats {
+            current_shot_type: ProjectileKind::Fireball,
+            ..Default::default()
+        };
+        assert_eq!(ready(&levels, &stats), Some(Evolution::Hellfire));
+    }
+
+    #[test]
+    fn evolved_weapons_do_not_evolve_again() {
+        let levels = levels(&[
+            (UpgradeSlot::Weapon(ProjectileKind::Normal), EVOLVE_LEVEL),
+            (UpgradeSlot::Multishot, 1),
+        ]);
+        let stats = WeaponStats {
+            evolved: vec![ProjectileKind::Normal],
+            ..Default::default()
+        };
+        assert_eq!(ready(&levels, &stats), None);
+    }
+}
@@ -0,0 +1,351 @@
+//! Shared pathfinding for chasing enemies. A grid centred near the player is marked
+//! blocked wherever an enemy body would overlap an obstacle, then a Dijkstra pass from
+//! the player's cell gives every open cell its distance to the player. Each cell points
+//! at its cheapest neighbour, so any number of enemies can steer around obstacles by
+//! sampling one shared field.
+//!
+//! The field only does work when something changes: blocked cells are recomputed when
+//! obstacles appear or disappear or the grid recentres, and distances when the player
+//! moves into a different cell.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::prelude::*;
+
+use crate::{
+    background::TILE_SIZE,
+    obstacle::{self, Collider, Obstacle},
+    player::Player,
+    state::{GameState, PauseState},
+};
+
+const CELL_SIZE: f32 = TILE_SIZE;
+/// Cells along each side of the grid.
+const GRID_CELLS: i32 = 64;
+/// How far (in cells) the player may drift from the grid centre before it recentres.
+const RECENTER_DISTANCE: i32 = 8;
+
+const NEIGHBOURS: [(IVec2, f32); 8] = [
+    (IVec2::new(1, 0), 1.0),
+    (IVec2::new(-1, 0), 1.0),
+    (IVec2::new(0, 1), 1.0),
+    (IVec2::new(0, -1), 1.0),
+    (IVec2::new(1, 1), std::f32::consts::SQRT_2),
+    (IVec2::new(1, -1), std::f32::consts::SQRT_2),
+    (IVec2::new(-1, 1), std::f32::consts::SQRT_2),
+    (IVec2::new(-1, -1), std::f32::consts::SQRT_2),
+];
+
+#[derive(Resource)]
+pub struct FlowField {
+    /// World cell coordinate of the grid's bottom-left cell.
+    origin: IVec2,
+    goal: Option<IVec2>,
+    blocked: Vec<bool>,
+    cost: Vec<f32>,
+    flow: Vec<Vec2>,
+    blocked_dirty: bool,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        let cells = (GRID_CELLS * GRID_CELLS) as usize;
+        Self {
+            origin: IVec2::ZERO,
+            goal: None,
+            blocked: vec![false; cells],
+            cost: vec![f32::INFINITY; cells],
+            flow: vec![Vec2::ZERO; cells],
+            blocked_dirty: true,
+        }
+    }
+}
+
+fn world_cell(pos: Vec2) -> IVec2 {
+    (pos / CELL_SIZE).floor().as_ivec2()
+}
+
+fn cell_center(cell: IVec2) -> Vec2 {
+    (cell.as_vec2() + 0.5) * CELL_SIZE
+}
+
+/// Cell range an enemy body touching a shape of `extent` around `center` can be in.
+fn cells_around(center: Vec2, extent: Vec2) -> (IVec2, IVec2) {
+    let extent = extent + obstacle::BODY_RADIUS;
+    (world_cell(center - extent), world_cell(center + extent))
+}
+
+impl FlowField {
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.origin;
+        (local.cmpge(IVec2::ZERO).all() && local.cmplt(IVec2::splat(GRID_CELLS)).all())
+            .then(|| (local.y * GRID_CELLS + local.x) as usize)
+    }
+
+    /// Direction to steer in from `pos`, or `None` when the field can't help (outside
+    /// the grid, unreachable, or already next to the player) and a straight line is best.
+    pub fn direction_at(&self, pos: Vec2) -> Option<Vec2> {
+        let index = self.index(world_cell(pos))?;
+        // Within a cell or so of the player the grid is too coarse to be useful
+        (self.cost[index].is_finite() && self.cost[index] > 1.5).then_some(self.flow[index])
+    }
+
+    fn rebuild_blocked(&mut self, obstacles: &Query<&Obstacle>) {
+        self.blocked.fill(false);
+        for obstacle in obstacles.iter() {
+            // Only cells near the obstacle can be blocked, except for bounds which block
+            // everything outside them
+            let (min, max) = match obstacle.collider {
+                Collider::Circle { radius } => cells_around(obstacle.center, Vec2::splat(radius)),
+                Collider::Rect { half_size } => cells_around(obstacle.center, half_size),
+                Collider::Bounds { .. } => (self.origin, self.origin + IVec2::splat(GRID_CELLS - 1)),
+            };
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    let cell = IVec2::new(x, y);
+                    let Some(index) = self.index(cell) else {
+                        continue;
+                    };
+                    // Blocked if an enemy standing at the cell centre would be pushed out
+                    if obstacle
+                        .collider
+                        .push_out(obstacle.center, cell_center(cell), obstacle::BODY_RADIUS)
+                        .is_some()
+                    {
+                        self.blocked[index] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn integrate(&mut self, goal: IVec2) {
+        self.cost.fill(f32::INFINITY);
+        self.flow.fill(Vec2::ZERO);
+        let Some(goal_index) = self.index(goal) else {
+            return;
+        };
+
+        let mut open = BinaryHeap::new();
+        self.cost[goal_index] = 0.0;
+        open.push(OpenCell { cost: 0.0, cell: goal });
+
+        while let Some(OpenCell { cost, cell }) = open.pop() {
+            let index = self.index(cell).unwrap();
+            if cost > self.cost[index] {
+                continue;
+            }
+            for (step, step_cost) in NEIGHBOURS {
+                let next = cell + step;
+                let Some(next_index) = self.index(next) else {
+                    continue;
+                };
+                if self.blocked[next_index] || self.cuts_corner(cell, step) {
+                    continue;
+                }
+                let next_cost = cost + step_cost;
+                if next_cost < self.cost[next_index] {
+                    self.cost[next_index] = next_cost;
+                    open.push(OpenCell {
+                        cost: next_cost,
+                        cell: next,
+                    });
+                }
+            }
+        }
+
+        for y in 0..GRID_CELLS {
+            for x in 0..GRID_CELLS {
+                let cell = self.origin + IVec2::new(x, y);
+                let index = self.index(cell).unwrap();
+                if !self.cost[index].is_finite() {
+                    continue;
+                }
+                let best = NEIGHBOURS
+                    .iter()
+                    .filter(|(step, _)| !self.cuts_corner(cell, *step))
+                    .filter_map(|(step, _)| {
+                        let next_index = self.index(cell + *step)?;
+                        Some((*step, self.cost[next_index]))
+                    })
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((step, cost)) = best
+                    && cost < self.cost[index]
+                {
+                    self.flow[index] = step.as_vec2().normalize();
+                }
+            }
+        }
+    }
+
+    /// Diagonal steps may not squeeze between two blocked orthogonal cells.
+    fn cuts_corner(&self, cell: IVec2, step: IVec2) -> bool {
+        if step.x == 0 || step.y == 0 {
+            return false;
+        }
+        let blocked = |c: IVec2| self.index(c).is_none_or(|i| self.blocked[i]);
+        blocked(cell + IVec2::new(step.x, 0)) || blocked(cell + IVec2::new(0, step.y))
+    }
+}
+
+/// Min-heap entry for the Dijkstra pass.
+struct OpenCell {
+    cost: f32,
+    cell: IVec2,
+}
+
+impl PartialEq for OpenCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for OpenCell {}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so BinaryHeap pops the cheapest cell first
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+pub struct FlowFieldPlugin;
+
+impl Plugin for FlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FlowField::default())
+            .add_systems(OnExit(GameState::Playing), reset_flow_field)
+            .add_systems(
+                Update,
+                update_flow_field.run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn update_flow_field(
+    mut field: ResMut<FlowField>,
+    player_q: Query<&Transform, With<Player>>,
+    obstacle_q: Query<&Obstacle>,
+    added_q: Query<(), Added<Obstacle>>,
+    mut removed: RemovedComponents<Obstacle>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    let goal = world_cell(player_t.translation.truncate());
+
+    if !added_q.is_empty() || removed.read().count() > 0 {
+        field.blocked_dirty = true;
+    }
+
+    let center = field.origin + IVec2::splat(GRID_CELLS / 2);
+    if (goal - center).abs().max_element() > RECENTER_DISTANCE {
+        field.origin = goal - IVec2::splat(GRID_CELLS / 2);
+        field.blocked_dirty = true;
+    }
+
+    let rebuilt = field.blocked_dirty;
+    if rebuilt {
+        field.rebuild_blocked(&obstacle_q);
+        field.blocked_dirty = false;
+    }
+    if rebuilt || field.goal != Some(goal) {
+        field.goal = Some(goal);
+        field.integrate(goal);
+    }
+}
+
+fn reset_flow_field(mut field: ResMut<FlowField>) {
+    *field = FlowField::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_with(blocked: impl IntoIterator<Item = IVec2>) -> FlowField {
+        let mut field = FlowField::default();
+        for cell in blocked {
+            let index = field.index(cell).unwrap();
+            field.blocked[index] = true;
+        }
+        field
+    }
+
+    /// Follows the flow from `start`, returning the cells visited until it stops.
+    fn follow(field: &FlowField, start: IVec2) -> Vec<IVec2> {
+        let mut path = vec![start];
+        let mut cell = start;
+        while let Some(index) = field.index(cell) {
+            let step = field.flow[index].round().as_ivec2();
+            if step == IVec2::ZERO || path.len() > 200 {
+                break;
+            }
+            assert!(!field.cuts_corner(cell, step), "cut a corner at {cell}");
+            cell += step;
+            path.push(cell);
+        }
+        path
+    }
+
+    #[test]
+    fn open_field_costs_straight_line_distance() {
+        let mut field = field_with([]);
+        field.integrate(IVec2::new(20, 10));
+        let index = field.index(IVec2::new(10, 10)).unwrap();
+        assert_eq!(field.cost[index], 10.0);
+        assert_eq!(field.flow[index], Vec2::X);
+    }
+
+    #[test]
+    fn routes_around_a_wall() {
+        // Wall at x = 10 from the bottom of the grid up to y = 20
+        let wall = (0..=20).map(|y| IVec2::new(10, y)).collect::<Vec<_>>();
+        let mut field = field_with(wall.iter().copied());
+        let goal = IVec2::new(15, 5);
+        field.integrate(goal);
+
+        let start = IVec2::new(5, 5);
+        assert!(field.cost[field.index(start).unwrap()] > 30.0);
+        let path = follow(&field, start);
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|cell| !wall.contains(cell)));
+        assert!(path.iter().any(|cell| cell.y > 20));
+    }
+
+    #[test]
+    fn diagonal_gaps_are_closed() {
+        // Two cells touching at a corner leave no way through diagonally
+        let mut field = field_with([IVec2::new(6, 5), IVec2::new(5, 6)]);
+        field.integrate(IVec2::new(6, 6));
+
+        let start = IVec2::new(5, 5);
+        assert!(field.cost[field.index(start).unwrap()] > std::f32::consts::SQRT_2);
+        assert_ne!(field.flow[field.index(start).unwrap()].round().as_ivec2(), IVec2::ONE);
+        assert_eq!(follow(&field, start).last(), Some(&IVec2::new(6, 6)));
+    }
+
+    #[test]
+    fn walled_off_cells_are_unreachable() {
+        let ring = [
+            IVec2::new(4, 4),
+            IVec2::new(5, 4),
+            IVec2::new(6, 4),
+            IVec2::new(4, 5),
+            IVec2::new(6, 5),
+            IVec2::new(4, 6),
+            IVec2::new(5, 6),
+            IVec2::new(6, 6),
+        ];
+        let mut field = field_with(ring);
+        field.integrate(IVec2::new(20, 20));
+        assert!(!field.cost[field.index(IVec2::new(5, 5)).unwrap()].is_finite());
+        assert_eq!(field.direction_at(cell_center(IVec2::new(5, 5))), None);
+    }
+}
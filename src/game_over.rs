@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::{
+    damage_meter::{self, DamageMeter},
+    gold::RunGold,
+    level::PlayerXP,
+    menu::{self, MenuActivated},
+    state::{GameState, RunStats, RunTimer},
+};
+
+#[derive(Component)]
+enum GameOverButton {
+    PlayAgain,
+    MainMenu,
+    Leaderboard,
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over)
+            .add_systems(
+                Update,
+                handle_game_over_buttons.run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+fn spawn_game_over(
+    mut commands: Commands,
+    run_timer: Res<RunTimer>,
+    xp: Res<PlayerXP>,
+    run_gold: Res<RunGold>,
+    meter: Res<DamageMeter>,
+    run_stats: Res<RunStats>,
+) {
+    let elapsed = run_timer.0.elapsed_secs();
+    let secs = elapsed as u32;
+
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::GameOver)))
+        .with_children(|parent| {
+            menu::spawn_title(
+                parent,
+                match (run_stats.cleared, run_stats.quit) {
+                    (true, _) => "Stage Cleared!",
+                    (false, true) => "Run Ended",
+                    (false, false) => "Game Over",
+                },
+            );
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Survived {:02}:{:02} - reached level {} - earned {} gold",
+                    secs / 60,
+                    secs % 60,
+                    xp.level,
+                    run_gold.0
+                ),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            // Whole-run average DPS; the last-10s figure from the overlay means little after death
+            let breakdown = damage_meter::breakdown_lines(&meter, |tally| {
+                tally.total_damage / elapsed.max(f32::EPSILON)
+            });
+            parent.spawn(
+                TextBundle::from_section(
+                    breakdown.join("\n"),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+            menu::spawn_button(parent, 0, "1. Play Again", GameOverButton::PlayAgain);
+            menu::spawn_button(parent, 1, "2. Main Menu", GameOverButton::MainMenu);
+            menu::spawn_button(parent, 2, "3. Leaderboard", GameOverButton::Leaderboard);
+        });
+}
+
+fn handle_game_over_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&GameOverButton>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        match button_q.get(ev.0) {
+            Ok(GameOverButton::PlayAgain) => next_state.set(GameState::Playing),
+            Ok(GameOverButton::MainMenu) => next_state.set(GameState::MainMenu),
+            Ok(GameOverButton::Leaderboard) => next_state.set(GameState::Leaderboard),
+            Err(_) => {}
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    pickup::{self, PickupCollected, PickupKind},
+    player::PlayerStats,
+    state::PauseState,
+};
+
+/// Gold picked up during the current run, banked into the profile when the run ends.
+#[derive(Resource, Default)]
+pub struct RunGold(pub u32);
+
+/// Chance that a slain enemy drops a coin.
+const GOLD_DROP_CHANCE: f64 = 0.3;
+
+pub fn maybe_spawn_gold(commands: &mut Commands, pos: Vec3, rng: &mut impl Rng) {
+    if rng.gen_bool(GOLD_DROP_CHANCE) {
+        spawn_gold(commands, pos + Vec3::new(8.0, 0.0, 0.0), rng.gen_range(1..=3));
+    }
+}
+
+pub fn spawn_gold(commands: &mut Commands, pos: Vec3, value: u32) {
+    pickup::spawn_pickup(commands, PickupKind::Gold, value, pos);
+}
+
+pub fn collect_gold(
+    mut collected: EventReader<PickupCollected>,
+    mut run_gold: ResMut<RunGold>,
+    stats: Res<PlayerStats>,
+) {
+    for ev in collected.read() {
+        if ev.kind == PickupKind::Gold {
+            run_gold.0 += (ev.value as f32 * stats.gold_multiplier).round() as u32;
+        }
+    }
+}
+
+pub struct GoldPlugin;
+
+impl Plugin for GoldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunGold::default())
+            .add_systems(Update, collect_gold.run_if(in_state(PauseState::Running)));
+    }
+}
@@ -1,17 +1,79 @@
 use bevy::{color::palettes::css, prelude::*};
 
-use crate::{enemy::Enemy, level, player::Player};
+use crate::{enemy::{Enemy, EnemyKilledEvent}, gold, level, pickup::{self, PickupKind}, player::Player, state::{GameState, RunRng, RunStats}, weapon::WeaponKind};
 
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Ice,
+    Poison,
+}
+
 #[derive(Event)]
 pub struct DamageEvent {
-    pub entity: Entity,   // Who should take the damage
-    pub amount: f32,      // How much damage
+    pub entity: Entity,         // Who should take the damage
+    pub amount: f32,            // How much damage, before the target's resistances
+    pub source: Option<Entity>, // What dealt it (projectile, trail, enemy), if anything
+    pub damage_type: DamageType,
+    pub crit: bool,
+    pub knockback: Vec2, // Impulse applied to targets that can be knocked back
+    pub weapon: Option<WeaponKind>, // Player weapon responsible, None for enemy attacks
 }
 
+/// Damage a target actually took, after its resistances. Sent for every `DamageEvent`
+/// that lands, so nothing else has to redo the resistance maths.
+#[derive(Event)]
+pub struct DamageApplied {
+    pub entity: Entity,
+    pub amount: f32,
+    pub position: Vec3, // Where the target was when hit
+    pub damage_type: DamageType,
+    pub crit: bool,
+    pub weapon: Option<WeaponKind>,
+}
+
+/// Damage multipliers per type; below 1 resists, above 1 is a weakness.
+#[derive(Component, Clone, Copy, Reflect)]
+pub struct Resistances {
+    pub physical: f32,
+    pub fire: f32,
+    pub ice: f32,
+    pub poison: f32,
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self {
+            physical: 1.0,
+            fire: 1.0,
+            ice: 1.0,
+            poison: 1.0,
+        }
+    }
+}
+
+impl Resistances {
+    pub fn apply(&self, amount: f32, damage_type: DamageType) -> f32 {
+        amount
+            * match damage_type {
+                DamageType::Physical => self.physical,
+                DamageType::Fire => self.fire,
+                DamageType::Ice => self.ice,
+                DamageType::Poison => self.poison,
+            }
+    }
+}
+
+/// Velocity from knockback, added on top of normal movement and decaying over time.
+#[derive(Component, Default)]
+pub struct Knockback(pub Vec2);
+
 #[derive(Event)]
 pub struct DeathEvent {
     pub entity: Entity,
+    pub killed_by: Option<WeaponKind>,
 }
 
 #[derive(Component, Reflect)]
@@ -26,6 +88,10 @@ impl Health {
     }
 }
 
+/// While present, the entity ignores all incoming damage (e.g. dash i-frames).
+#[derive(Component)]
+pub struct Invulnerable;
+
 #[derive(Component)]
 pub struct DamageCooldown {
     pub timer: Timer,
@@ -43,17 +109,53 @@ impl DamageCooldown {
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn apply_damage_system(
     mut damage_events: EventReader<DamageEvent>,
     mut death_writer: EventWriter<DeathEvent>,
-    mut health_q: Query<&mut Health>,
+    mut applied_writer: EventWriter<DamageApplied>,
+    mut health_q: Query<
+        (&mut Health, &Transform, Option<&Resistances>, Option<&mut Knockback>),
+        Without<Invulnerable>,
+    >,
+    player_q: Query<(), With<Player>>,
+    source_q: Query<&Enemy>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     for ev in damage_events.read() {
-        if let Ok(mut health) = health_q.get_mut(ev.entity) {
-            health.current -= ev.amount;
+        if let Ok((mut health, transform, resistances, knockback)) = health_q.get_mut(ev.entity) {
+            let was_alive = health.current > 0.0;
+            let amount = resistances
+                .copied()
+                .unwrap_or_default()
+                .apply(ev.amount, ev.damage_type);
+            health.current -= amount;
+            applied_writer.send(DamageApplied {
+                entity: ev.entity,
+                amount,
+                position: transform.translation,
+                damage_type: ev.damage_type,
+                crit: ev.crit,
+                weapon: ev.weapon,
+            });
+
+            if player_q.contains(ev.entity)
+                && let Some(enemy) = ev.source.and_then(|s| source_q.get(s).ok())
+            {
+                run_stats.last_hit_by = Some(enemy.enemy_type.definition().name);
+            }
 
-            if health.current <= 0.0 {
-                death_writer.send(DeathEvent { entity: ev.entity });
+            if let Some(mut knockback) = knockback {
+                knockback.0 += ev.knockback;
+            }
+
+            // Only the killing blow reports a death, so overlapping hits and DoT
+            // ticks in the same frame don't drop loot twice.
+            if was_alive && health.current <= 0.0 {
+                death_writer.send(DeathEvent {
+                    entity: ev.entity,
+                    killed_by: ev.weapon,
+                });
                 // Don't despawn here — leave that for the death system
             }
         }
@@ -63,12 +165,23 @@ pub fn apply_damage_system(
 pub fn enemy_death_system(
     mut commands: Commands,
     mut death_events: EventReader<DeathEvent>,
-    enemy_q: Query<&Transform, With<Enemy>>,
+    mut killed_writer: EventWriter<EnemyKilledEvent>,
+    enemy_q: Query<(&Transform, &Enemy)>,
+    mut run_rng: ResMut<RunRng>,
 ) {
     for ev in death_events.read() {
-        if let Ok(transform) = enemy_q.get(ev.entity) {
-            level::spawn_xp(&mut commands, transform.translation);
-            commands.entity(ev.entity).despawn();
+        if let Ok((transform, enemy)) = enemy_q.get(ev.entity) {
+            level::spawn_xp(&mut commands, transform.translation, enemy.enemy_type.definition().xp);
+            gold::maybe_spawn_gold(&mut commands, transform.translation, &mut run_rng.rng);
+            if enemy.rank.definition().drops_chest {
+                pickup::spawn_pickup(&mut commands, PickupKind::Chest, 0, transform.translation);
+            }
+            killed_writer.send(EnemyKilledEvent {
+                enemy_type: enemy.enemy_type,
+                killed_by: ev.killed_by,
+            });
+            // Recursive so the health bar goes too; any DoTs live on the enemy itself
+            commands.entity(ev.entity).despawn_recursive();
             println!("Enemy died, dropped XP!");
         }
     }
@@ -78,12 +191,13 @@ pub fn player_death_system(
     mut commands: Commands,
     mut death_events: EventReader<DeathEvent>,
     player_q: Query<Entity, With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
     for ev in death_events.read() {
         if player_q.get(ev.entity).is_ok() {
             println!("Player died! Game Over.");
-            // TODO: show game over UI, pause game, etc.
-            commands.entity(ev.entity).despawn();
+            commands.entity(ev.entity).despawn_recursive();
+            next_state.set(GameState::GameOver);
         }
     }
 }
@@ -124,6 +238,7 @@ pub fn spawn_health_bar(
     }
 }
 
+#[allow(clippy::collapsible_if)]
 pub fn update_health_bars(
     mut q: Query<(&Health, &Children)>,
     mut bar_q: Query<&mut Sprite, With<HealthBar>>,
@@ -0,0 +1,413 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character::{CharacterId, SelectedCharacter},
+    gold::RunGold,
+    level::PlayerXP,
+    menu::{self, MenuActivated},
+    player::PlayerStats,
+    projectile::ProjectileKind,
+    save,
+    state::{GameState, RunRng, RunStats, RunTimer},
+    weapon::WeaponStats,
+};
+
+/// The player's build at the end of a run.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildSnapshot {
+    pub shot_type: ProjectileKind,
+    pub multishot: u32,
+    pub spread_deg: f32,
+    pub projectile_speed: f32,
+    pub damage_multiplier: f32,
+    pub move_speed: f32,
+    pub max_health: f32,
+}
+
+/// Fields default when missing, so records written by other versions still load.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunRecord {
+    /// Game version that produced the run, for comparing balance changes.
+    pub game_version: String,
+    /// Unix timestamp of when the run ended.
+    pub finished_at: u64,
+    pub character: CharacterId,
+    pub seed: u64,
+    pub time_survived: f32,
+    pub level: u32,
+    pub kills: u32,
+    pub gold: u32,
+    pub build: BuildSnapshot,
+    pub cause_of_death: String,
+}
+
+/// Every finished run, stored as `history.ron` in the data dir.
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunHistory {
+    pub runs: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("survivors").join("history.ron"))
+    }
+
+    pub fn load() -> Self {
+        Self::path().map(|path| Self::load_from(&path)).unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|err| {
+            // Keep the damaged file around instead of overwriting it on the next save.
+            let aside = path.with_extension("ron.corrupt");
+            warn!(
+                "Run history {} is unreadable ({}), moved to {}",
+                path.display(),
+                err,
+                aside.display()
+            );
+            let _ = fs::rename(path, aside);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = Self::path() {
+            self.save_to(&path);
+        }
+    }
+
+    fn save_to(&self, path: &Path) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| save::write_atomic(path, &text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Could not save run history to {}: {}", path.display(), err);
+        }
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub enum LeaderboardSort {
+    #[default]
+    Time,
+    Level,
+    Kills,
+    Recent,
+}
+
+impl LeaderboardSort {
+    const ALL: [LeaderboardSort; 4] = [
+        LeaderboardSort::Time,
+        LeaderboardSort::Level,
+        LeaderboardSort::Kills,
+        LeaderboardSort::Recent,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            LeaderboardSort::Time => "Time",
+            LeaderboardSort::Level => "Level",
+            LeaderboardSort::Kills => "Kills",
+            LeaderboardSort::Recent => "Recent",
+        }
+    }
+}
+
+#[derive(Component)]
+enum LeaderboardButton {
+    Sort(LeaderboardSort),
+    Back,
+}
+
+#[derive(Component)]
+struct LeaderboardRows;
+
+const LEADERBOARD_SIZE: usize = 10;
+const COLUMN_WIDTHS: [f32; 8] = [30.0, 90.0, 60.0, 40.0, 50.0, 80.0, 90.0, 60.0];
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunHistory::load())
+            .insert_resource(LeaderboardSort::default())
+            .add_systems(OnEnter(GameState::GameOver), record_run)
+            .add_systems(OnEnter(GameState::Leaderboard), spawn_leaderboard)
+            .add_systems(
+                Update,
+                (handle_leaderboard_buttons, update_leaderboard_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Leaderboard)),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_run(
+    mut history: ResMut<RunHistory>,
+    selected: Res<SelectedCharacter>,
+    run_rng: Res<RunRng>,
+    run_timer: Res<RunTimer>,
+    run_stats: Res<RunStats>,
+    run_gold: Res<RunGold>,
+    xp: Res<PlayerXP>,
+    weapon_stats: Res<WeaponStats>,
+    player_stats: Res<PlayerStats>,
+) {
+    let finished_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    history.runs.push(RunRecord {
+        game_version: env!("CARGO_PKG_VERSION").to_string(),
+        finished_at,
+        character: selected.0,
+        seed: run_rng.seed,
+        time_survived: run_timer.0.elapsed_secs(),
+        level: xp.level,
+        kills: run_stats.kills.values().sum(),
+        gold: run_gold.0,
+        build: BuildSnapshot {
+            shot_type: weapon_stats.current_shot_type,
+            multishot: weapon_stats.multishot,
+            spread_deg: weapon_stats.spread_deg,
+            projectile_speed: weapon_stats.projectile_speed,
+            damage_multiplier: weapon_stats.damage_multiplier,
+            move_speed: player_stats.move_speed,
+            max_health: player_stats.max_health,
+        },
+        cause_of_death: if run_stats.cleared {
+            "Stage cleared".to_string()
+        } else if run_stats.quit {
+            "Quit".to_string()
+        } else {
+            run_stats.last_hit_by.unwrap_or("Unknown").to_string()
+        },
+    });
+    history.save();
+}
+
+fn spawn_row(parent: &mut ChildBuilder, cells: [String; 8], color: Color) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            for (cell, width) in cells.into_iter().zip(COLUMN_WIDTHS) {
+                row.spawn(
+                    TextBundle::from_section(
+                        cell,
+                        TextStyle {
+                            font_size: 16.0,
+                            color,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        width: Val::Px(width),
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+fn spawn_leaderboard(mut commands: Commands) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::Leaderboard)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Leaderboard");
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (i, sort) in LeaderboardSort::ALL.into_iter().enumerate() {
+                        menu::spawn_button(
+                            row,
+                            i,
+                            format!("Sort: {}", sort.label()),
+                            LeaderboardButton::Sort(sort),
+                        );
+                    }
+                });
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        margin: UiRect::vertical(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                LeaderboardRows,
+            ));
+            menu::spawn_button(
+                parent,
+                LeaderboardSort::ALL.len(),
+                "Back",
+                LeaderboardButton::Back,
+            );
+        });
+}
+
+fn handle_leaderboard_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&LeaderboardButton>,
+    mut sort: ResMut<LeaderboardSort>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        match button_q.get(ev.0) {
+            Ok(LeaderboardButton::Sort(s)) => *sort = *s,
+            Ok(LeaderboardButton::Back) => next_state.set(GameState::MainMenu),
+            Err(_) => {}
+        }
+    }
+}
+
+fn update_leaderboard_rows(
+    mut commands: Commands,
+    history: Res<RunHistory>,
+    sort: Res<LeaderboardSort>,
+    rows_q: Query<Entity, With<LeaderboardRows>>,
+    added_q: Query<(), Added<LeaderboardRows>>,
+) {
+    if !sort.is_changed() && added_q.is_empty() {
+        return;
+    }
+    let Ok(rows_e) = rows_q.get_single() else {
+        return;
+    };
+
+    let mut runs = history.runs.iter().collect::<Vec<_>>();
+    match *sort {
+        LeaderboardSort::Time => runs.sort_by(|a, b| b.time_survived.total_cmp(&a.time_survived)),
+        LeaderboardSort::Level => runs.sort_by_key(|r| std::cmp::Reverse(r.level)),
+        LeaderboardSort::Kills => runs.sort_by_key(|r| std::cmp::Reverse(r.kills)),
+        LeaderboardSort::Recent => runs.sort_by_key(|r| std::cmp::Reverse(r.finished_at)),
+    }
+
+    commands.entity(rows_e).despawn_descendants().with_children(|parent| {
+        let header = ["#", "Character", "Time", "Lvl", "Kills", "Shot", "Ended by", "Version"];
+        spawn_row(parent, header.map(String::from), Color::srgb(1.0, 0.85, 0.2));
+
+        for (i, run) in runs.into_iter().take(LEADERBOARD_SIZE).enumerate() {
+            let secs = run.time_survived as u32;
+            spawn_row(
+                parent,
+                [
+                    format!("{}", i + 1),
+                    run.character.definition().name.to_string(),
+                    format!("{:02}:{:02}", secs / 60, secs % 60),
+                    run.level.to_string(),
+                    run.kills.to_string(),
+                    format!("{:?}", run.build.shot_type),
+                    run.cause_of_death.clone(),
+                    run.game_version.clone(),
+                ],
+                Color::WHITE,
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("survivors-history-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(path.with_extension("ron.corrupt"));
+        path
+    }
+
+    fn sample_run() -> RunRecord {
+        RunRecord {
+            game_version: "0.1.0".to_string(),
+            finished_at: 1_700_000_000,
+            seed: 42,
+            time_survived: 312.5,
+            level: 14,
+            kills: 870,
+            gold: 95,
+            build: BuildSnapshot {
+                shot_type: ProjectileKind::Ice,
+                multishot: 3,
+                ..Default::default()
+            },
+            cause_of_death: "Werewolf".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_round_trips() {
+        let path = temp_path("round_trip.ron");
+        let history = RunHistory {
+            runs: vec![sample_run(), sample_run()],
+        };
+        history.save_to(&path);
+
+        let loaded = RunHistory::load_from(&path);
+        assert_eq!(loaded.runs.len(), 2);
+        assert_eq!(loaded.runs[0].kills, 870);
+        assert_eq!(loaded.runs[0].build.shot_type, ProjectileKind::Ice);
+        assert_eq!(loaded.runs[1].cause_of_death, "Werewolf");
+    }
+
+    #[test]
+    fn records_missing_fields_still_load() {
+        let path = temp_path("older.ron");
+        fs::write(&path, "(runs: [(level: 7, kills: 120)])").unwrap();
+
+        let loaded = RunHistory::load_from(&path);
+        assert_eq!(loaded.runs.len(), 1);
+        assert_eq!(loaded.runs[0].level, 7);
+        assert_eq!(loaded.runs[0].cause_of_death, "");
+    }
+
+    #[test]
+    fn unreadable_history_is_moved_aside() {
+        let path = temp_path("corrupt.ron");
+        fs::write(&path, "not ron at all").unwrap();
+
+        assert!(RunHistory::load_from(&path).runs.is_empty());
+        let aside = path.with_extension("ron.corrupt");
+        assert_eq!(fs::read_to_string(&aside).unwrap(), "not ron at all");
+
+        // Saving afterwards starts a new file and leaves the damaged one alone
+        RunHistory {
+            runs: vec![sample_run()],
+        }
+        .save_to(&path);
+        assert_eq!(fs::read_to_string(&aside).unwrap(), "not ron at all");
+        assert_eq!(RunHistory::load_from(&path).runs.len(), 1);
+    }
+}
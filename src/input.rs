@@ -0,0 +1,282 @@
+use std::{fs, io, path::PathBuf};
+
+use bevy::{input::InputSystem, prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Confirm,
+    Pause,
+    Dash,
+    ToggleStats,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Confirm,
+        Action::Pause,
+        Action::Dash,
+        Action::ToggleStats,
+    ];
+}
+
+/// Keys and gamepad buttons that trigger a single action.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Binding {
+    pub keys: Vec<KeyCode>,
+    pub gamepad: Vec<GamepadButtonType>,
+}
+
+impl Binding {
+    fn new(keys: &[KeyCode], gamepad: &[GamepadButtonType]) -> Self {
+        Self {
+            keys: keys.to_vec(),
+            gamepad: gamepad.to_vec(),
+        }
+    }
+}
+
+/// User-editable bindings, loaded from and saved to `input.ron` in the config dir.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub move_up: Binding,
+    pub move_down: Binding,
+    pub move_left: Binding,
+    pub move_right: Binding,
+    pub confirm: Binding,
+    pub pause: Binding,
+    pub dash: Binding,
+    pub toggle_stats: Binding,
+    /// Left stick deflection below this is ignored.
+    pub stick_dead_zone: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+        Self {
+            move_up: Binding::new(&[KeyCode::KeyW, KeyCode::ArrowUp], &[Pad::DPadUp]),
+            move_down: Binding::new(&[KeyCode::KeyS, KeyCode::ArrowDown], &[Pad::DPadDown]),
+            move_left: Binding::new(&[KeyCode::KeyA, KeyCode::ArrowLeft], &[Pad::DPadLeft]),
+            move_right: Binding::new(&[KeyCode::KeyD, KeyCode::ArrowRight], &[Pad::DPadRight]),
+            confirm: Binding::new(&[KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space], &[Pad::South]),
+            pause: Binding::new(&[KeyCode::Escape], &[Pad::Start]),
+            dash: Binding::new(&[KeyCode::ShiftLeft], &[Pad::East, Pad::RightTrigger]),
+            toggle_stats: Binding::new(&[KeyCode::Tab], &[Pad::Select]),
+            stick_dead_zone: 0.2,
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn binding(&self, action: Action) -> &Binding {
+        match action {
+            Action::MoveUp => &self.move_up,
+            Action::MoveDown => &self.move_down,
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Confirm => &self.confirm,
+            Action::Pause => &self.pause,
+            Action::Dash => &self.dash,
+            Action::ToggleStats => &self.toggle_stats,
+        }
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("survivors").join("input.ron"))
+    }
+
+    /// Loads the bindings file, writing out the defaults if there isn't one yet so
+    /// players have something to edit. A file that can't be read or parsed is left alone.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => ron::from_str(&text).unwrap_or_else(|err| {
+                warn!("Ignoring invalid input bindings in {}: {}", path.display(), err);
+                Self::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let bindings = Self::default();
+                bindings.save();
+                bindings
+            }
+            Err(err) => {
+                warn!("Could not read input bindings from {}: {}", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+                }
+                fs::write(&path, text).map_err(|err| err.to_string())
+            });
+        if let Err(err) = result {
+            warn!("Could not save input bindings to {}: {}", path.display(), err);
+        }
+    }
+}
+
+/// Per-frame snapshot of the bound actions, so gameplay never reads raw devices.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    /// Movement direction, length at most 1. Analog when driven by a stick.
+    pub move_axis: Vec2,
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load())
+            .insert_resource(ActionState::default())
+            .add_systems(PreUpdate, update_action_state.after(InputSystem));
+    }
+}
+
+fn update_action_state(
+    bindings: Res<InputBindings>,
+    kb: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    pad_buttons: Res<ButtonInput<GamepadButton>>,
+    pad_axes: Res<Axis<GamepadAxis>>,
+    mut state: ResMut<ActionState>,
+) {
+    state.pressed.clear();
+    state.just_pressed.clear();
+
+    for action in Action::ALL {
+        let binding = bindings.binding(action);
+        let pad = |button_type: GamepadButtonType| {
+            gamepads
+                .iter()
+                .map(move |gamepad| GamepadButton::new(gamepad, button_type))
+        };
+
+        let pressed = kb.any_pressed(binding.keys.iter().copied())
+            || binding
+                .gamepad
+                .iter()
+                .any(|&b| pad(b).any(|button| pad_buttons.pressed(button)));
+        let just_pressed = kb.any_just_pressed(binding.keys.iter().copied())
+            || binding
+                .gamepad
+                .iter()
+                .any(|&b| pad(b).any(|button| pad_buttons.just_pressed(button)));
+
+        if pressed {
+            state.pressed.insert(action);
+        }
+        if just_pressed {
+            state.just_pressed.insert(action);
+        }
+    }
+
+    let mut digital = Vec2::ZERO;
+    if state.pressed(Action::MoveUp) {
+        digital.y += 1.0;
+    }
+    if state.pressed(Action::MoveDown) {
+        digital.y -= 1.0;
+    }
+    if state.pressed(Action::MoveLeft) {
+        digital.x -= 1.0;
+    }
+    if state.pressed(Action::MoveRight) {
+        digital.x += 1.0;
+    }
+
+    state.move_axis = if digital != Vec2::ZERO {
+        digital.normalize()
+    } else {
+        gamepads
+            .iter()
+            .map(|gamepad| {
+                let x = pad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or(0.0);
+                let y = pad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                    .unwrap_or(0.0);
+                apply_dead_zone(Vec2::new(x, y), bindings.stick_dead_zone)
+            })
+            .find(|v| *v != Vec2::ZERO)
+            .unwrap_or(Vec2::ZERO)
+    };
+}
+
+/// Radial dead zone, rescaled so output still ramps smoothly from 0 to 1.
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let len = stick.length();
+    if len <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((len - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON)).min(1.0);
+    stick / len * scaled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_zone_swallows_small_deflection() {
+        assert_eq!(apply_dead_zone(Vec2::new(0.1, 0.1), 0.2), Vec2::ZERO);
+        // Exactly on the edge still counts as inside
+        assert_eq!(apply_dead_zone(Vec2::new(0.2, 0.0), 0.2), Vec2::ZERO);
+    }
+
+    #[test]
+    fn dead_zone_ramps_from_the_edge() {
+        let just_out = apply_dead_zone(Vec2::new(0.21, 0.0), 0.2);
+        assert!(just_out.x > 0.0 && just_out.x < 0.05);
+        assert_eq!(just_out.y, 0.0);
+
+        let halfway = apply_dead_zone(Vec2::new(0.0, -0.6), 0.2);
+        assert!((halfway.y + 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn full_deflection_stays_full() {
+        let full = apply_dead_zone(Vec2::new(1.0, 0.0), 0.2);
+        assert!((full.length() - 1.0).abs() < 1e-5);
+
+        // Diagonals past the unit circle are clamped, keeping their direction
+        let diagonal = apply_dead_zone(Vec2::new(1.0, 1.0), 0.2);
+        assert!((diagonal.length() - 1.0).abs() < 1e-5);
+        assert!((diagonal.x - diagonal.y).abs() < 1e-5);
+    }
+}
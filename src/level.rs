@@ -1,6 +1,13 @@
-use bevy::prelude::*;
+use std::time::Duration;
 
-use crate::player::Player;
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{pickup::{self, Magnetized, Pickup, PickupCollected, PickupKind}, player::Player, state::PauseState};
+
+/// Once more gems than this are lying around, far-off ones are merged into one.
+const MAX_GEMS: usize = 150;
+/// Gems closer to the player than this are never merged, so nothing visibly vanishes.
+const MERGE_DISTANCE: f32 = 700.0;
 
 #[derive(Resource)]
 pub struct PlayerXP {
@@ -20,68 +27,148 @@ impl Default for PlayerXP {
         }
     }
 }
+/// Level ups that haven't had their upgrade picked yet. One gem can be worth several.
 #[derive(Resource, Default)]
-pub struct IsUpgradeMenuOpen(pub bool);
+pub struct PendingLevelUps(pub u32);
 
 #[derive(Event)]
 pub struct LevelUpEvent;
 
 
-#[derive(Component)]
-pub struct XP;
+/// XP gems come in tiers by value; the tier only changes how a gem looks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GemTier {
+    Blue,
+    Green,
+    Red,
+}
+
+pub struct GemTierDefinition {
+    pub name: &'static str,
+    pub color: Color,
+    pub size: f32,
+    /// Smallest value shown as this tier.
+    pub min_value: u32,
+}
+
+impl GemTier {
+    /// Highest tier first.
+    const ALL: [GemTier; 3] = [GemTier::Red, GemTier::Green, GemTier::Blue];
 
-pub fn spawn_xp(commands: &mut Commands, pos: Vec3) {
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_translation(pos).with_scale(Vec3::splat(8.0)),
-            sprite: Sprite {
+    pub fn definition(&self) -> GemTierDefinition {
+        match self {
+            GemTier::Blue => GemTierDefinition {
+                name: "Blue Gem",
                 color: Color::srgb(0.2, 0.6, 1.0),
-                ..default()
+                size: 8.0,
+                min_value: 0,
+            },
+            GemTier::Green => GemTierDefinition {
+                name: "Green Gem",
+                color: Color::srgb(0.3, 0.9, 0.4),
+                size: 10.0,
+                min_value: 5,
+            },
+            GemTier::Red => GemTierDefinition {
+                name: "Red Gem",
+                color: Color::srgb(1.0, 0.25, 0.3),
+                size: 13.0,
+                min_value: 25,
             },
-            ..default()
-        },
-        XP,
-        Name::new("XP Orb")
-    ));
+        }
+    }
+
+    pub fn for_value(value: u32) -> GemTier {
+        GemTier::ALL
+            .into_iter()
+            .find(|tier| value >= tier.definition().min_value)
+            .unwrap_or(GemTier::Blue)
+    }
 }
 
-pub fn xp_collection(
+pub fn spawn_xp(commands: &mut Commands, pos: Vec3, value: u32) {
+    pickup::spawn_pickup(commands, PickupKind::Xp, value, pos);
+}
+
+/// Dresses XP gems as their tier whenever their value is set.
+fn style_gems(mut gem_q: Query<(&Pickup, &mut Sprite, &mut Transform, &mut Name), Changed<Pickup>>) {
+    for (pickup, mut sprite, mut transform, mut name) in gem_q.iter_mut() {
+        if pickup.kind != PickupKind::Xp {
+            continue;
+        }
+        let def = GemTier::for_value(pickup.value).definition();
+        sprite.color = def.color;
+        // Turned on end so gems read as diamonds rather than squares
+        *transform = transform
+            .with_scale(Vec3::splat(def.size))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4));
+        name.set(def.name);
+    }
+}
+
+/// Keeps the gem count bounded by folding every far-off gem into the one of them
+/// nearest the player.
+fn merge_distant_gems(
     mut commands: Commands,
-    mut param: ParamSet<(
-        Query<(Entity, &mut Transform), With<XP>>,
-        Query<&Transform, With<Player>>,
-    )>,
+    mut gem_q: Query<(Entity, &Transform, &mut Pickup), Without<Magnetized>>,
+    player_q: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    let player_pos = player_t.translation.truncate();
+
+    let mut distant = Vec::new();
+    let mut count = 0;
+    for (entity, transform, pickup) in gem_q.iter() {
+        if pickup.kind != PickupKind::Xp {
+            continue;
+        }
+        count += 1;
+        let dist = transform.translation.truncate().distance(player_pos);
+        if dist > MERGE_DISTANCE {
+            distant.push((entity, dist));
+        }
+    }
+    if count <= MAX_GEMS || distant.len() < 2 {
+        return;
+    }
+
+    distant.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let (keep, _) = distant[0];
+    let mut total = 0;
+    for &(entity, _) in &distant[1..] {
+        total += gem_q.get(entity).map(|(_, _, pickup)| pickup.value).unwrap_or(0);
+        commands.entity(entity).despawn();
+    }
+    if let Ok((_, _, mut pickup)) = gem_q.get_mut(keep) {
+        pickup.value += total;
+    }
+}
+
+/// Adds collected XP, levelling up as many times as it covers. A gem's value is a number
+/// of orbs, each worth `orb_value`.
+pub fn collect_xp(
+    mut collected: EventReader<PickupCollected>,
     mut xp_resource: ResMut<PlayerXP>,
     mut level_up_writer: EventWriter<LevelUpEvent>,
-    time: Res<Time>,
 ) {
-    let player_query = param.p1();
-    let player_t = player_query.single();
-    let player_pos = player_t.translation.truncate();
+    for ev in collected.read() {
+        if ev.kind != PickupKind::Xp {
+            continue;
+        }
+        xp_resource.current += ev.value * xp_resource.orb_value;
+
+        while xp_resource.current >= xp_resource.required {
+            xp_resource.current -= xp_resource.required;
+            xp_resource.level += 1;
+            xp_resource.required = (xp_resource.required as f32 * 1.5).ceil() as u32;
 
-    for (xp_e, mut xp_t) in param.p0().iter_mut() {
-        let xp_pos = xp_t.translation.truncate();
-        let dist = xp_pos.distance(player_pos);
-
-        if dist < 20.0 {
-            commands.entity(xp_e).despawn();
-            xp_resource.current += xp_resource.orb_value;
-
-            if xp_resource.current >= xp_resource.required {
-                xp_resource.current -= xp_resource.required;
-                xp_resource.level += 1;
-                xp_resource.required = (xp_resource.required as f32 * 1.5).ceil() as u32;
-
-                println!(
-                    "🎉 Level Up! Level {}, Next: {}",
-                    xp_resource.level, xp_resource.required
-                );
-                level_up_writer.send(LevelUpEvent);
-            }
-        } else if dist < 150.0 {
-            let direction = (player_pos - xp_pos).normalize_or_zero();
-            let speed = 200.0;
-            xp_t.translation += (direction * speed * time.delta_seconds()).extend(0.0);
+            println!(
+                "🎉 Level Up! Level {}, Next: {}",
+                xp_resource.level, xp_resource.required
+            );
+            level_up_writer.send(LevelUpEvent);
         }
     }
 }
@@ -91,8 +178,30 @@ pub struct XPPlugin;
 impl Plugin for XPPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<LevelUpEvent>()
-            .insert_resource(IsUpgradeMenuOpen::default())
+            .insert_resource(PendingLevelUps::default())
             .insert_resource(PlayerXP::default())
-            .add_systems(Update, xp_collection);
+            .add_systems(Update, (collect_xp, style_gems).run_if(in_state(PauseState::Running)))
+            .add_systems(
+                Update,
+                merge_distant_gems
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(on_timer(Duration::from_secs(1))),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gem_tiers_change_at_thresholds() {
+        assert_eq!(GemTier::for_value(0), GemTier::Blue);
+        assert_eq!(GemTier::for_value(1), GemTier::Blue);
+        assert_eq!(GemTier::for_value(4), GemTier::Blue);
+        assert_eq!(GemTier::for_value(5), GemTier::Green);
+        assert_eq!(GemTier::for_value(24), GemTier::Green);
+        assert_eq!(GemTier::for_value(25), GemTier::Red);
+        assert_eq!(GemTier::for_value(u32::MAX), GemTier::Red);
     }
 }
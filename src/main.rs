@@ -1,17 +1,48 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-use crate::{enemy::{EnemySpawnTimer}, health::{DamageEvent, DeathEvent, Health}, level::XPPlugin, player::PlayerPlugin, projectile::Projectile};
+use crate::{achievement::AchievementPlugin, aura::{AuraPlugin, AuraStats}, background::BackgroundPlugin, brazier::BrazierPlugin, camera::CameraPlugin, character::CharacterPlugin, chest::ChestPlugin, damage_meter::{DamageMeter, DamageMeterPlugin}, damage_numbers::DamageNumberPlugin, gold::{GoldPlugin, RunGold}, history::HistoryPlugin, profile::ProfilePlugin, settings::SettingsPlugin, shop::ShopPlugin, stage::StagePlugin, dash::{DashPlugin, DashStats}, dot::DotPlugin, flow_field::FlowFieldPlugin, enemy::{BossWave, EnemyFreeze, EnemyKilledEvent, EnemySpawnTimer}, game_over::GameOverPlugin, health::{DamageApplied, DamageEvent, DeathEvent, Health, Resistances}, input::ActionsPlugin, level::{PendingLevelUps, PlayerXP, XPPlugin}, main_menu::MainMenuPlugin, menu::MenuPlugin, obstacle::ObstaclePlugin, orbit::{OrbitPlugin, OrbitStats}, pause::PausePlugin, pickup::PickupPlugin, player::{PlayerPlugin, PlayerStats}, projectile::Projectile, run_setup::RunSetupPlugin, state::{GameState, PauseState, RunRng, RunStats, RunTimer}, upgrade::UpgradeLevels, weapon::{WeaponStats, WeaponTimer}};
 
+mod achievement;
+mod aura;
+mod background;
+mod brazier;
+mod camera;
+mod character;
+mod chest;
+mod damage_meter;
+mod damage_numbers;
+mod dash;
+mod dot;
 mod enemy;
+mod evolution;
+mod flow_field;
+mod game_over;
+mod gold;
+mod input;
 mod level;
+mod main_menu;
+mod menu;
+mod obstacle;
+mod orbit;
+mod pause;
+mod pickup;
 mod player;
+mod profile;
 mod projectile;
+mod run_setup;
+mod save;
+mod settings;
+mod shapes;
+mod shop;
+mod stage;
+mod state;
 mod ui;
 mod upgrade;
 mod upgrade_menu;
 mod weapon;
 mod health;
+mod history;
 
 fn main() {
     App::new()
@@ -27,21 +58,46 @@ fn main() {
                     ..default()
                 }),
         )
+        .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
         .add_event::<DamageEvent>()
+        .add_event::<DamageApplied>()
         .add_event::<DeathEvent>()
-        .add_plugins((PlayerPlugin, XPPlugin, WorldInspectorPlugin::default()))
+        .add_event::<EnemyKilledEvent>()
+        .add_plugins((ActionsPlugin, PlayerPlugin, DashPlugin, XPPlugin, MenuPlugin, CharacterPlugin, GameOverPlugin, GoldPlugin, ProfilePlugin, ShopPlugin, AchievementPlugin, HistoryPlugin, DamageNumberPlugin, DotPlugin, WorldInspectorPlugin::default()))
+        .add_plugins((DamageMeterPlugin, PausePlugin, SettingsPlugin, CameraPlugin, MainMenuPlugin, RunSetupPlugin, BackgroundPlugin, ObstaclePlugin, FlowFieldPlugin, StagePlugin, BrazierPlugin, PickupPlugin, ChestPlugin, OrbitPlugin, AuraPlugin))
         .insert_resource(weapon::WeaponTimer::default())
         .insert_resource(weapon::WeaponStats::default())
         .register_type::<Health>()
+        .register_type::<Resistances>()
         .register_type::<Projectile>()
         .register_type::<EnemySpawnTimer>()
         .insert_resource(enemy::EnemySpawnTimer::default())
-        .add_systems(Startup, (
-            
-            setup, 
-            ui::setup_xp_bar,
-          
-        ))
+        .insert_resource(EnemyFreeze::default())
+        .insert_resource(BossWave::default())
+        .insert_resource(RunTimer::default())
+        .insert_resource(RunStats::default())
+        .insert_resource(RunRng::default())
+        .insert_resource(UpgradeLevels::default())
+        .add_systems(Startup, setup)
+        .add_systems(
+            OnEnter(GameState::Playing),
+            (
+                reset_run,
+                character::apply_starting_kit,
+                shop::apply_power_ups,
+                run_setup::apply_modifiers,
+                player::spawn_player,
+                ui::setup_xp_bar,
+                ui::setup_hud,
+            )
+                .chain(),
+        )
+        .add_systems(OnEnter(PauseState::LevelUp), upgrade_menu::open_upgrade_menu)
+        .add_systems(
+            Update,
+            upgrade_menu::handle_upgrade_selection.run_if(in_state(PauseState::LevelUp)),
+        )
         .add_systems(
             Update,
             (
@@ -50,10 +106,15 @@ fn main() {
                 enemy::enemy_movement,
                 enemy::difficulty_scaling,
                 enemy::enemy_spawner,
+                (enemy::tick_enemy_freeze, enemy::tint_enemies).chain(),
                 projectile::projectile_enemy_collision,
-                upgrade_menu::show_upgrade_menu,
-                upgrade_menu::handle_upgrade_selection,
-                ui::update_xp_bar,
+                upgrade_menu::queue_level_ups,
+                (
+                    ui::update_xp_bar,
+                    ui::update_hud_text,
+                    ui::update_hud_health,
+                    ui::update_hud_slots,
+                ),
                 enemy::enemy_player_collision,
                 health::apply_damage_system,
                 health::enemy_death_system,
@@ -62,7 +123,9 @@ fn main() {
                 health::spawn_health_bar,
                 health::update_health_bars,
                 projectile::projectile_bounds_cleanup,
-            ),
+                state::tick_run_timer,
+            )
+                .run_if(in_state(PauseState::Running)),
         )
         .run();
 }
@@ -70,3 +133,25 @@ fn main() {
 fn setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
 }
+
+/// Puts every per-run resource back to its starting value before a new run begins. The
+/// systems chained after it see the fresh values, since the chain applies commands in between.
+fn reset_run(mut commands: Commands) {
+    commands.insert_resource(WeaponTimer::default());
+    commands.insert_resource(WeaponStats::default());
+    commands.insert_resource(EnemySpawnTimer::default());
+    commands.insert_resource(PlayerStats::default());
+    commands.insert_resource(DashStats::default());
+    commands.insert_resource(PlayerXP::default());
+    commands.insert_resource(PendingLevelUps::default());
+    commands.insert_resource(RunTimer::default());
+    commands.insert_resource(RunGold::default());
+    commands.insert_resource(RunStats::default());
+    commands.insert_resource(RunRng::default());
+    commands.insert_resource(UpgradeLevels::default());
+    commands.insert_resource(DamageMeter::default());
+    commands.insert_resource(EnemyFreeze::default());
+    commands.insert_resource(BossWave::default());
+    commands.insert_resource(OrbitStats::default());
+    commands.insert_resource(AuraStats::default());
+}
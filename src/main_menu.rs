@@ -0,0 +1,107 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    menu::{self, MenuActivated},
+    settings::{self, SettingsClosed},
+    state::GameState,
+};
+
+#[derive(Component, Clone, Copy)]
+enum MainMenuButton {
+    Start,
+    Characters,
+    PowerUps,
+    Achievements,
+    Leaderboard,
+    Settings,
+    Quit,
+}
+
+impl MainMenuButton {
+    const ALL: [MainMenuButton; 7] = [
+        MainMenuButton::Start,
+        MainMenuButton::Characters,
+        MainMenuButton::PowerUps,
+        MainMenuButton::Achievements,
+        MainMenuButton::Leaderboard,
+        MainMenuButton::Settings,
+        MainMenuButton::Quit,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            MainMenuButton::Start => "Start",
+            MainMenuButton::Characters => "Characters",
+            MainMenuButton::PowerUps => "Power Ups",
+            MainMenuButton::Achievements => "Achievements",
+            MainMenuButton::Leaderboard => "Leaderboard",
+            MainMenuButton::Settings => "Settings",
+            MainMenuButton::Quit => "Quit",
+        }
+    }
+}
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnEnter(GameState::Settings), spawn_settings)
+            .add_systems(
+                Update,
+                handle_main_menu_buttons.run_if(in_state(GameState::MainMenu)),
+            )
+            .add_systems(
+                Update,
+                close_settings.run_if(in_state(GameState::Settings)),
+            );
+    }
+}
+
+fn spawn_main_menu(mut commands: Commands) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::MainMenu)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Vampire Survivors Clone");
+            for (i, button) in MainMenuButton::ALL.into_iter().enumerate() {
+                menu::spawn_button(parent, i, format!("{}. {}", i + 1, button.label()), button);
+            }
+        });
+}
+
+fn spawn_settings(mut commands: Commands) {
+    settings::spawn_settings_screen(&mut commands, GameState::Settings);
+}
+
+fn handle_main_menu_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&MainMenuButton>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for ev in activated.read() {
+        let Ok(button) = button_q.get(ev.0) else {
+            continue;
+        };
+        match button {
+            MainMenuButton::Start => next_state.set(GameState::RunSetup),
+            MainMenuButton::Characters => next_state.set(GameState::Characters),
+            MainMenuButton::PowerUps => next_state.set(GameState::PowerUps),
+            MainMenuButton::Achievements => next_state.set(GameState::Achievements),
+            MainMenuButton::Leaderboard => next_state.set(GameState::Leaderboard),
+            MainMenuButton::Settings => next_state.set(GameState::Settings),
+            MainMenuButton::Quit => {
+                exit.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+fn close_settings(
+    mut closed: EventReader<SettingsClosed>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if closed.read().count() > 0 {
+        next_state.set(GameState::MainMenu);
+    }
+}
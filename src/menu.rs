@@ -0,0 +1,203 @@
+use bevy::{color::palettes::css, prelude::*};
+
+use crate::input::{Action, ActionState};
+
+/// Marks a button that can receive focus from the keyboard or a gamepad.
+/// Items are navigated in ascending `order`.
+#[derive(Component)]
+pub struct MenuItem {
+    pub order: usize,
+}
+
+/// The menu item that currently has focus, if any.
+#[derive(Resource, Default)]
+pub struct MenuFocus(pub Option<Entity>);
+
+/// Sent when a menu item is chosen, whether by mouse click, confirm key or number shortcut.
+#[derive(Event)]
+pub struct MenuActivated(pub Entity);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuActivated>()
+            .insert_resource(MenuFocus::default())
+            .add_systems(
+                Update,
+                (mouse_interaction, menu_navigation, highlight_focus).chain(),
+            );
+    }
+}
+
+/// Visible items in navigation order; hidden menus (e.g. behind the pause screen) are skipped.
+fn sorted_items(items: &Query<(Entity, &MenuItem, &InheritedVisibility)>) -> Vec<Entity> {
+    let mut sorted = items
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(e, item, _)| (e, item))
+        .collect::<Vec<_>>();
+    sorted.sort_by_key(|(e, item)| (item.order, *e));
+    sorted.into_iter().map(|(e, _)| e).collect()
+}
+
+#[allow(clippy::type_complexity)]
+fn mouse_interaction(
+    mut focus: ResMut<MenuFocus>,
+    mut activated: EventWriter<MenuActivated>,
+    interaction_q: Query<(Entity, &Interaction), (Changed<Interaction>, With<MenuItem>)>,
+) {
+    for (entity, interaction) in interaction_q.iter() {
+        match interaction {
+            Interaction::Pressed => {
+                focus.0 = Some(entity);
+                activated.send(MenuActivated(entity));
+            }
+            Interaction::Hovered => focus.0 = Some(entity),
+            Interaction::None => {}
+        }
+    }
+}
+
+fn menu_navigation(
+    kb: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    mut focus: ResMut<MenuFocus>,
+    mut activated: EventWriter<MenuActivated>,
+    items: Query<(Entity, &MenuItem, &InheritedVisibility)>,
+) {
+    let sorted = sorted_items(&items);
+    if sorted.is_empty() {
+        focus.0 = None;
+        return;
+    }
+
+    let mut index = match focus.0.and_then(|e| sorted.iter().position(|&s| s == e)) {
+        Some(i) => i,
+        None => {
+            focus.0 = Some(sorted[0]);
+            0
+        }
+    };
+
+    let prev = actions.just_pressed(Action::MoveUp) || actions.just_pressed(Action::MoveLeft);
+    let next = actions.just_pressed(Action::MoveDown) || actions.just_pressed(Action::MoveRight);
+
+    if prev {
+        index = (index + sorted.len() - 1) % sorted.len();
+    }
+    if next {
+        index = (index + 1) % sorted.len();
+    }
+    focus.0 = Some(sorted[index]);
+
+    if actions.just_pressed(Action::Confirm) {
+        activated.send(MenuActivated(sorted[index]));
+        return;
+    }
+
+    // Number keys jump straight to the n-th item.
+    const SHORTCUTS: [KeyCode; 9] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (i, key) in SHORTCUTS.iter().enumerate() {
+        if kb.just_pressed(*key) {
+            if let Some(&entity) = sorted.get(i) {
+                focus.0 = Some(entity);
+                activated.send(MenuActivated(entity));
+            }
+            break;
+        }
+    }
+}
+
+fn highlight_focus(focus: Res<MenuFocus>, mut q: Query<(Entity, &mut BorderColor), With<MenuItem>>) {
+    for (entity, mut border) in q.iter_mut() {
+        let color = if focus.0 == Some(entity) {
+            Color::WHITE
+        } else {
+            Color::NONE
+        };
+        if border.0 != color {
+            border.0 = color;
+        }
+    }
+}
+
+/// Full-screen, centred column used as the root of every menu screen.
+pub fn root_node() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
+        ..default()
+    }
+}
+
+pub fn spawn_title(parent: &mut ChildBuilder, text: impl Into<String>) {
+    parent.spawn(
+        TextBundle::from_section(
+            text,
+            TextStyle {
+                font_size: 40.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::bottom(Val::Px(20.0)),
+            ..default()
+        }),
+    );
+}
+
+/// Spawns a focusable button with a text label. `marker` identifies the button to
+/// whichever system handles its `MenuActivated` events.
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    order: usize,
+    label: impl Into<String>,
+    marker: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    margin: UiRect::all(Val::Px(6.0)),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: css::DARK_GRAY.into(),
+                ..default()
+            },
+            MenuItem { order },
+            marker,
+        ))
+        .with_children(|p| {
+            p.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
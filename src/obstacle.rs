@@ -0,0 +1,345 @@
+//! Static obstacles. Anything that moves resolves against them by being pushed back
+//! out along the shortest direction, which makes it slide along edges instead of
+//! sticking to them.
+
+use bevy::prelude::*;
+
+use crate::{
+    background::{self, BackgroundChunk, CHUNK_TILES, TILE_SIZE},
+    projectile::Projectile,
+    run_setup::RunSetup,
+    shapes::CircleTexture,
+    stage::StageBounds,
+    state::{GameState, PauseState, RunRng},
+};
+
+/// Collision radius of the player and of enemies.
+pub const BODY_RADIUS: f32 = 14.0;
+const PROJECTILE_RADIUS: f32 = 4.0;
+/// Obstacles are never scattered this close to where the player starts.
+const SAFE_RADIUS: f32 = 200.0;
+
+#[derive(Clone, Copy, Debug, Reflect)]
+pub enum Collider {
+    Circle { radius: f32 },
+    Rect { half_size: Vec2 },
+    /// Inside out: keeps bodies within a rectangle rather than out of it.
+    Bounds { half_size: Vec2 },
+}
+
+impl Collider {
+    /// Where a circle at `pos` has to move to stop overlapping this collider centred at
+    /// `center`, or `None` if they don't overlap.
+    pub fn push_out(&self, center: Vec2, pos: Vec2, radius: f32) -> Option<Vec2> {
+        match *self {
+            Collider::Circle { radius: r } => {
+                let offset = pos - center;
+                let min_dist = r + radius;
+                (offset.length_squared() < min_dist * min_dist)
+                    .then(|| center + offset.normalize_or(Vec2::X) * min_dist)
+            }
+            Collider::Rect { half_size } => {
+                let closest = pos.clamp(center - half_size, center + half_size);
+                let offset = pos - closest;
+                if offset == Vec2::ZERO {
+                    // Centre is inside the rect: leave through the nearest side.
+                    let local = pos - center;
+                    let depth = half_size - local.abs();
+                    return Some(if depth.x < depth.y {
+                        Vec2::new(center.x + (half_size.x + radius) * local.x.signum(), pos.y)
+                    } else {
+                        Vec2::new(pos.x, center.y + (half_size.y + radius) * local.y.signum())
+                    });
+                }
+                (offset.length_squared() < radius * radius)
+                    .then(|| closest + offset.normalize() * radius)
+            }
+            Collider::Bounds { half_size } => {
+                let inner = (half_size - radius).max(Vec2::ZERO);
+                let clamped = pos.clamp(center - inner, center + inner);
+                (clamped != pos).then_some(clamped)
+            }
+        }
+    }
+}
+
+/// What a projectile does when it hits an obstacle.
+#[derive(Clone, Copy, PartialEq, Debug, Reflect)]
+pub enum ProjectileResponse {
+    Stop,
+    Bounce,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect)]
+pub enum ObstacleKind {
+    Rock,
+    Tree,
+    Wall,
+    /// The edge of an arena stage.
+    Boundary,
+}
+
+pub struct ObstacleDefinition {
+    pub name: &'static str,
+    pub color: Color,
+    pub collider: Collider,
+    pub projectiles: ProjectileResponse,
+}
+
+impl ObstacleKind {
+    pub fn definition(&self) -> ObstacleDefinition {
+        match self {
+            ObstacleKind::Rock => ObstacleDefinition {
+                name: "Rock",
+                color: Color::srgb(0.45, 0.45, 0.5),
+                collider: Collider::Circle { radius: 22.0 },
+                projectiles: ProjectileResponse::Bounce,
+            },
+            ObstacleKind::Tree => ObstacleDefinition {
+                name: "Tree",
+                color: Color::srgb(0.15, 0.35, 0.12),
+                collider: Collider::Circle { radius: 28.0 },
+                projectiles: ProjectileResponse::Stop,
+            },
+            ObstacleKind::Wall => ObstacleDefinition {
+                name: "Wall",
+                color: Color::srgb(0.5, 0.42, 0.35),
+                collider: Collider::Rect {
+                    half_size: Vec2::new(80.0, 14.0),
+                },
+                projectiles: ProjectileResponse::Bounce,
+            },
+            ObstacleKind::Boundary => ObstacleDefinition {
+                name: "Arena Edge",
+                color: Color::NONE,
+                // Sized by the stage when it is spawned
+                collider: Collider::Bounds {
+                    half_size: Vec2::ZERO,
+                },
+                projectiles: ProjectileResponse::Stop,
+            },
+        }
+    }
+}
+
+/// How a stage places its obstacles.
+pub enum ObstacleLayout {
+    /// Sprinkled over the endless ground as chunks load; each entry is a kind and the
+    /// chance of one appearing on any given tile.
+    Scattered(&'static [(ObstacleKind, f32)]),
+    /// Placed once at the start of the run at fixed world positions.
+    Fixed(&'static [(ObstacleKind, Vec2)]),
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    /// World position, kept here so collision doesn't wait on transform propagation.
+    pub center: Vec2,
+    pub collider: Collider,
+}
+
+/// Moves a body of `radius` at `pos` out of every obstacle it overlaps.
+pub fn resolve(pos: Vec2, radius: f32, obstacles: &Query<&Obstacle>) -> Vec2 {
+    obstacles.iter().fold(pos, |pos, obstacle| {
+        obstacle
+            .collider
+            .push_out(obstacle.center, pos, radius)
+            .unwrap_or(pos)
+    })
+}
+
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Collider>()
+            .init_resource::<CircleTexture>()
+            .add_systems(OnEnter(GameState::Playing), spawn_stage_obstacles)
+            .add_systems(
+                Update,
+                spawn_chunk_obstacles.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                projectile_obstacle_collision.run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+/// Sprite for an obstacle drawn at `translation` relative to its parent.
+fn obstacle_sprite(kind: ObstacleKind, translation: Vec3, circle: &CircleTexture) -> SpriteBundle {
+    let def = kind.definition();
+    let (texture, size) = match def.collider {
+        Collider::Circle { radius } => (circle.0.clone(), Vec2::splat(radius * 2.0)),
+        Collider::Rect { half_size } | Collider::Bounds { half_size } => {
+            (Handle::default(), half_size * 2.0)
+        }
+    };
+    SpriteBundle {
+        sprite: Sprite {
+            color: def.color,
+            custom_size: Some(size),
+            ..default()
+        },
+        texture,
+        transform: Transform::from_translation(translation),
+        ..default()
+    }
+}
+
+/// Fixed obstacles and arena walls, which live for the whole run.
+fn spawn_stage_obstacles(mut commands: Commands, setup: Res<RunSetup>, circle: Res<CircleTexture>) {
+    let stage = setup.stage.definition();
+
+    if let StageBounds::Arena { half_size } = stage.bounds {
+        commands.spawn((
+            Obstacle {
+                kind: ObstacleKind::Boundary,
+                center: Vec2::ZERO,
+                collider: Collider::Bounds { half_size },
+            },
+            Name::new(ObstacleKind::Boundary.definition().name),
+            StateScoped(GameState::Playing),
+        ));
+    }
+
+    let ObstacleLayout::Fixed(placed) = stage.obstacles else {
+        return;
+    };
+    for &(kind, center) in placed {
+        let def = kind.definition();
+        commands.spawn((
+            obstacle_sprite(kind, center.extend(-5.0), &circle),
+            Obstacle {
+                kind,
+                center,
+                collider: def.collider,
+            },
+            Name::new(def.name),
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+fn spawn_chunk_obstacles(
+    mut commands: Commands,
+    chunk_q: Query<(Entity, &BackgroundChunk), Added<BackgroundChunk>>,
+    setup: Res<RunSetup>,
+    run_rng: Res<RunRng>,
+    circle: Res<CircleTexture>,
+) {
+    let ObstacleLayout::Scattered(kinds) = setup.stage.definition().obstacles else {
+        return;
+    };
+
+    for (chunk_e, chunk) in chunk_q.iter() {
+        commands.entity(chunk_e).with_children(|parent| {
+            for tx in 0..CHUNK_TILES {
+                for ty in 0..CHUNK_TILES {
+                    let tile = chunk.0 * CHUNK_TILES + IVec2::new(tx, ty);
+                    let local = (Vec2::new(tx as f32, ty as f32) + 0.5) * TILE_SIZE;
+                    let center = chunk.0.as_vec2() * CHUNK_TILES as f32 * TILE_SIZE + local;
+                    if center.length() < SAFE_RADIUS {
+                        continue;
+                    }
+
+                    // Separate hash stream per kind so densities don't interfere
+                    let Some(&(kind, _)) = kinds.iter().enumerate().find_map(|(i, entry)| {
+                        let roll = background::hash(tile.x, tile.y, run_rng.seed ^ (0x0b57 + i as u64));
+                        (roll < entry.1).then_some(entry)
+                    }) else {
+                        continue;
+                    };
+
+                    let def = kind.definition();
+                    parent.spawn((
+                        // Chunk root sits at z -10; obstacles draw above the ground
+                        obstacle_sprite(kind, local.extend(5.0), &circle),
+                        Obstacle {
+                            kind,
+                            center,
+                            collider: def.collider,
+                        },
+                        Name::new(def.name),
+                    ));
+                }
+            }
+        });
+    }
+}
+
+fn projectile_obstacle_collision(
+    mut commands: Commands,
+    mut projectile_q: Query<(Entity, &mut Transform, &mut Projectile)>,
+    obstacle_q: Query<&Obstacle>,
+) {
+    for (proj_e, mut transform, mut proj) in projectile_q.iter_mut() {
+        let pos = transform.translation.truncate();
+        for obstacle in obstacle_q.iter() {
+            let Some(pushed) = obstacle.collider.push_out(obstacle.center, pos, PROJECTILE_RADIUS)
+            else {
+                continue;
+            };
+            match obstacle.kind.definition().projectiles {
+                ProjectileResponse::Stop => {
+                    commands.entity(proj_e).despawn();
+                }
+                ProjectileResponse::Bounce => {
+                    let normal = (pushed - pos).normalize_or_zero();
+                    let direction = proj.direction;
+                    proj.direction = direction - 2.0 * direction.dot(normal) * normal;
+                    transform.translation = pushed.extend(transform.translation.z);
+                }
+            }
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_pushes_out_along_offset() {
+        let rock = Collider::Circle { radius: 10.0 };
+        let pushed = rock.push_out(Vec2::ZERO, Vec2::new(5.0, 0.0), 4.0).unwrap();
+        assert_eq!(pushed, Vec2::new(14.0, 0.0));
+        assert_eq!(rock.push_out(Vec2::ZERO, Vec2::new(20.0, 0.0), 4.0), None);
+    }
+
+    #[test]
+    fn circle_at_centre_still_pushes_out() {
+        let rock = Collider::Circle { radius: 10.0 };
+        let pushed = rock.push_out(Vec2::ZERO, Vec2::ZERO, 4.0).unwrap();
+        assert_eq!(pushed.length(), 14.0);
+    }
+
+    #[test]
+    fn rect_pushes_out_from_edge() {
+        let wall = Collider::Rect { half_size: Vec2::new(10.0, 20.0) };
+        let pushed = wall.push_out(Vec2::ZERO, Vec2::new(12.0, 5.0), 4.0).unwrap();
+        assert_eq!(pushed, Vec2::new(14.0, 5.0));
+        assert_eq!(wall.push_out(Vec2::ZERO, Vec2::new(15.0, 5.0), 4.0), None);
+    }
+
+    #[test]
+    fn rect_inside_leaves_through_nearest_side() {
+        let wall = Collider::Rect { half_size: Vec2::new(10.0, 20.0) };
+        // Closer to the right side than to the top
+        let pushed = wall.push_out(Vec2::ZERO, Vec2::new(8.0, 15.0), 4.0).unwrap();
+        assert_eq!(pushed, Vec2::new(14.0, 15.0));
+        // Closer to the bottom than to either side
+        let pushed = wall.push_out(Vec2::ZERO, Vec2::new(1.0, -19.0), 4.0).unwrap();
+        assert_eq!(pushed, Vec2::new(1.0, -24.0));
+    }
+
+    #[test]
+    fn bounds_keep_bodies_inside() {
+        let arena = Collider::Bounds { half_size: Vec2::splat(100.0) };
+        assert_eq!(arena.push_out(Vec2::ZERO, Vec2::new(10.0, -20.0), 4.0), None);
+        let pushed = arena.push_out(Vec2::ZERO, Vec2::new(98.0, 150.0), 4.0).unwrap();
+        assert_eq!(pushed, Vec2::new(96.0, 96.0));
+    }
+}
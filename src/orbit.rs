@@ -0,0 +1,232 @@
+//! Orbiting tomes. Every cooldown, the tomes appear around the player and circle them for
+//! a while, damaging enemies they pass through. Each tome hits a given enemy at most once
+//! per rotation.
+
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    enemy::Enemy,
+    health::{DamageEvent, DamageType},
+    player::{Player, PlayerStats},
+    state::{GameState, PauseState, RunRng},
+    weapon::{WeaponKind, WeaponStats},
+};
+
+const HIT_RADIUS: f32 = 24.0;
+const KNOCKBACK: f32 = 60.0;
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct OrbitStats {
+    pub count: u32, // 0 until the weapon is picked up
+    pub radius: f32,
+    pub speed: f32, // Radians per second
+    pub duration: f32,
+    pub cooldown: f32,
+    pub damage: f32,
+}
+
+impl Default for OrbitStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            radius: 80.0,
+            speed: 3.0,
+            duration: 3.0,
+            cooldown: 3.0,
+            damage: 20.0,
+        }
+    }
+}
+
+/// Whether the tomes are out, and how long until that changes.
+#[derive(Resource)]
+struct OrbitCycle {
+    timer: Timer,
+    active: bool,
+    angle: f32,
+}
+
+impl Default for OrbitCycle {
+    fn default() -> Self {
+        // Starts finished so the tomes come out as soon as they are owned
+        Self {
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+            active: false,
+            angle: 0.0,
+        }
+    }
+}
+
+/// Angle of one tome, with the tomes spread evenly around the circle.
+fn orbiter_angle(base: f32, index: u32, count: u32) -> f32 {
+    base + index as f32 * TAU / count.max(1) as f32
+}
+
+/// Seconds for one full rotation, so a tome hits each enemy once per pass.
+fn rotation_time(speed: f32) -> f32 {
+    TAU / speed.max(0.1)
+}
+
+#[derive(Component)]
+struct Orbiter {
+    index: u32,
+    /// Seconds until each enemy can be hit again.
+    hit_cooldowns: HashMap<Entity, f32>,
+}
+
+pub struct OrbitPlugin;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(OrbitStats::default())
+            .insert_resource(OrbitCycle::default())
+            .register_type::<OrbitStats>()
+            .add_systems(OnExit(GameState::Playing), reset_cycle)
+            .add_systems(
+                Update,
+                (orbit_cycle, orbit_movement, orbit_hits)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn orbit_cycle(
+    mut commands: Commands,
+    time: Res<Time>,
+    stats: Res<OrbitStats>,
+    mut cycle: ResMut<OrbitCycle>,
+    orbiter_q: Query<Entity, With<Orbiter>>,
+) {
+    if stats.count == 0 || !cycle.timer.tick(time.delta()).finished() {
+        return;
+    }
+
+    if cycle.active {
+        for orbiter_e in orbiter_q.iter() {
+            commands.entity(orbiter_e).despawn();
+        }
+        cycle.timer = Timer::from_seconds(stats.cooldown, TimerMode::Once);
+    } else {
+        for index in 0..stats.count {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgb(0.95, 0.9, 0.6),
+                        custom_size: Some(Vec2::new(14.0, 18.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Orbiter {
+                    index,
+                    hit_cooldowns: HashMap::new(),
+                },
+                Name::new("Orbiting Tome"),
+                StateScoped(GameState::Playing),
+            ));
+        }
+        cycle.timer = Timer::from_seconds(stats.duration, TimerMode::Once);
+    }
+    cycle.active = !cycle.active;
+}
+
+fn orbit_movement(
+    time: Res<Time>,
+    stats: Res<OrbitStats>,
+    player_stats: Res<PlayerStats>,
+    mut cycle: ResMut<OrbitCycle>,
+    player_q: Query<&Transform, With<Player>>,
+    mut orbiter_q: Query<(&mut Transform, &Orbiter), Without<Player>>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    cycle.angle = (cycle.angle + stats.speed * time.delta_seconds()) % TAU;
+
+    let count = orbiter_q.iter().count() as u32;
+    let radius = stats.radius * player_stats.area;
+    for (mut transform, orbiter) in orbiter_q.iter_mut() {
+        let angle = orbiter_angle(cycle.angle, orbiter.index, count);
+        let pos = player_t.translation.truncate() + Vec2::from_angle(angle) * radius;
+        transform.translation = pos.extend(5.0);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn orbit_hits(
+    time: Res<Time>,
+    stats: Res<OrbitStats>,
+    weapon_stats: Res<WeaponStats>,
+    mut run_rng: ResMut<RunRng>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut orbiter_q: Query<(Entity, &Transform, &mut Orbiter)>,
+    enemy_q: Query<(Entity, &Transform), With<Enemy>>,
+    player_q: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    let rotation_time = rotation_time(stats.speed);
+
+    for (orbiter_e, orbiter_t, mut orbiter) in orbiter_q.iter_mut() {
+        orbiter.hit_cooldowns.retain(|_, remaining| {
+            *remaining -= time.delta_seconds();
+            *remaining > 0.0
+        });
+
+        for (enemy_e, enemy_t) in enemy_q.iter() {
+            if orbiter.hit_cooldowns.contains_key(&enemy_e)
+                || orbiter_t.translation.truncate().distance(enemy_t.translation.truncate())
+                    >= HIT_RADIUS
+            {
+                continue;
+            }
+            let (amount, crit) = weapon_stats.roll_hit(&mut run_rng.rng, stats.damage);
+            let away = (enemy_t.translation - player_t.translation).truncate().normalize_or_zero();
+            damage_writer.send(DamageEvent {
+                entity: enemy_e,
+                amount,
+                source: Some(orbiter_e),
+                damage_type: DamageType::Physical,
+                crit,
+                knockback: away * KNOCKBACK,
+                weapon: Some(WeaponKind::Orbit),
+            });
+            orbiter.hit_cooldowns.insert(enemy_e, rotation_time);
+        }
+    }
+}
+
+fn reset_cycle(mut cycle: ResMut<OrbitCycle>) {
+    *cycle = OrbitCycle::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tomes_are_spread_evenly() {
+        let angles = (0..4).map(|i| orbiter_angle(0.5, i, 4)).collect::<Vec<_>>();
+        for pair in angles.windows(2) {
+            assert!((pair[1] - pair[0] - TAU / 4.0).abs() < 1e-5);
+        }
+        assert_eq!(orbiter_angle(0.5, 0, 1), 0.5);
+        // No division by zero before the tomes are counted
+        assert!(orbiter_angle(0.5, 0, 0).is_finite());
+    }
+
+    #[test]
+    fn hit_cooldown_lasts_one_rotation() {
+        assert!((rotation_time(3.0) - TAU / 3.0).abs() < 1e-5);
+        // Faster tomes come round sooner
+        assert!(rotation_time(6.0) < rotation_time(3.0));
+        // A stalled tome still lets enemies be hit again eventually
+        assert!(rotation_time(0.0).is_finite());
+    }
+}
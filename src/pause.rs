@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, ActionState},
+    menu::{self, MenuActivated},
+    settings::{self, SettingsClosed},
+    state::{GameState, PauseState, RunStats},
+};
+
+#[derive(Component)]
+enum PauseButton {
+    Resume,
+    Settings,
+    Quit,
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PauseState>()
+            .enable_state_scoped_entities::<PauseState>()
+            .add_systems(OnExit(PauseState::Running), pause_time)
+            .add_systems(OnEnter(PauseState::Running), resume_time)
+            // Quitting from the pause menu leaves Playing without passing through Running
+            .add_systems(OnExit(GameState::Playing), resume_time)
+            .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+            .add_systems(OnEnter(PauseState::Settings), spawn_settings)
+            .add_systems(
+                Update,
+                (toggle_pause, handle_pause_buttons, close_settings)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Freezes `Time<Virtual>`, which stops every gameplay timer and movement at once.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn toggle_pause(
+    actions: Res<ActionState>,
+    state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+    next_state.set(match state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+        PauseState::Settings => PauseState::Paused,
+        // The chest or upgrade choice has to be dismissed first
+        PauseState::Chest | PauseState::LevelUp => return,
+    });
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((menu::root_node(), StateScoped(PauseState::Paused)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Paused");
+            menu::spawn_button(parent, 0, "1. Resume", PauseButton::Resume);
+            menu::spawn_button(parent, 1, "2. Settings", PauseButton::Settings);
+            menu::spawn_button(parent, 2, "3. End Run", PauseButton::Quit);
+        });
+}
+
+fn spawn_settings(mut commands: Commands) {
+    settings::spawn_settings_screen(&mut commands, PauseState::Settings);
+}
+
+fn handle_pause_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&PauseButton>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for ev in activated.read() {
+        match button_q.get(ev.0) {
+            Ok(PauseButton::Resume) => next_pause.set(PauseState::Running),
+            Ok(PauseButton::Settings) => next_pause.set(PauseState::Settings),
+            // Ends the run like a death, so its gold is banked and it shows in the history
+            Ok(PauseButton::Quit) => {
+                run_stats.quit = true;
+                next_state.set(GameState::GameOver);
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+fn close_settings(
+    mut closed: EventReader<SettingsClosed>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+) {
+    if closed.read().count() > 0 {
+        next_pause.set(PauseState::Paused);
+    }
+}
@@ -0,0 +1,271 @@
+//! Everything the player can pick up off the floor goes through one pipeline: pickups
+//! within the player's magnet range drift towards them, and touching one despawns it and
+//! sends a `PickupCollected` event. Each kind's effect is applied by whichever module
+//! owns it (XP in `level`, gold in `gold`, chests in `chest`, the rest here).
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    camera::CameraShake,
+    enemy::{Enemy, EnemyFreeze, EnemyRank},
+    health::{DamageEvent, DamageType, Health},
+    player::{Player, PlayerStats},
+    state::{GameState, PauseState},
+};
+
+const PICKUP_RADIUS: f32 = 20.0;
+const ATTRACT_SPEED: f32 = 200.0;
+const VACUUM_SPEED: f32 = 600.0;
+/// Value of the coin a prop drops, well above an enemy's.
+const PROP_GOLD: u32 = 10;
+/// HP restored by food.
+const FOOD_HEAL: f32 = 30.0;
+/// Seconds enemies stay frozen after a clock is picked up.
+const FREEZE_DURATION: f32 = 5.0;
+/// Damage a bomb deals to each enemy on screen; enough for a normal or elite enemy.
+const BOMB_DAMAGE: f32 = 500.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PickupKind {
+    Xp,
+    Gold,
+    Food,
+    Magnet,
+    Bomb,
+    Clock,
+    Chest,
+}
+
+pub struct PickupDefinition {
+    pub name: &'static str,
+    pub color: Color,
+    pub size: f32,
+}
+
+impl PickupKind {
+    pub fn definition(&self) -> PickupDefinition {
+        match self {
+            PickupKind::Xp => PickupDefinition {
+                name: "XP Orb",
+                color: Color::srgb(0.2, 0.6, 1.0),
+                size: 8.0,
+            },
+            PickupKind::Gold => PickupDefinition {
+                name: "Gold Coin",
+                color: Color::srgb(1.0, 0.85, 0.2),
+                size: 7.0,
+            },
+            PickupKind::Food => PickupDefinition {
+                name: "Roast Chicken",
+                color: Color::srgb(0.85, 0.45, 0.3),
+                size: 10.0,
+            },
+            PickupKind::Magnet => PickupDefinition {
+                name: "Magnet",
+                color: Color::srgb(0.8, 0.2, 0.8),
+                size: 10.0,
+            },
+            PickupKind::Bomb => PickupDefinition {
+                name: "Bomb",
+                color: Color::srgb(0.2, 0.2, 0.2),
+                size: 10.0,
+            },
+            PickupKind::Clock => PickupDefinition {
+                name: "Freeze Clock",
+                color: Color::srgb(0.5, 0.9, 1.0),
+                size: 10.0,
+            },
+            PickupKind::Chest => PickupDefinition {
+                name: "Treasure Chest",
+                color: Color::srgb(0.75, 0.5, 0.15),
+                size: 18.0,
+            },
+        }
+    }
+}
+
+/// What a breakable prop can drop, with relative weights.
+const PROP_DROPS: [(PickupKind, u32); 5] = [
+    (PickupKind::Food, 30),
+    (PickupKind::Magnet, 12),
+    (PickupKind::Gold, 35),
+    (PickupKind::Bomb, 10),
+    (PickupKind::Clock, 13),
+];
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    /// How much XP or gold it is worth; unused by other kinds.
+    pub value: u32,
+}
+
+/// Pulled to the player from any distance, e.g. by a magnet pickup.
+#[derive(Component)]
+pub struct Magnetized;
+
+#[derive(Event)]
+pub struct PickupCollected {
+    pub kind: PickupKind,
+    pub value: u32,
+}
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PickupCollected>().add_systems(
+            Update,
+            (pickup_collection, apply_pickup_effects)
+                .chain()
+                .run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+pub fn spawn_pickup(commands: &mut Commands, kind: PickupKind, value: u32, pos: Vec3) {
+    let def = kind.definition();
+    commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_translation(pos).with_scale(Vec3::splat(def.size)),
+            sprite: Sprite {
+                color: def.color,
+                ..default()
+            },
+            ..default()
+        },
+        Pickup { kind, value },
+        Name::new(def.name),
+        StateScoped(GameState::Playing),
+    ));
+}
+
+/// Drops a random pickup from a broken prop at `pos`.
+pub fn spawn_random_drop(commands: &mut Commands, pos: Vec3, rng: &mut impl Rng) {
+    let (kind, _) = *PROP_DROPS.choose_weighted(rng, |(_, weight)| *weight).unwrap();
+    let value = if kind == PickupKind::Gold { PROP_GOLD } else { 0 };
+    spawn_pickup(commands, kind, value, pos);
+}
+
+fn pickup_collection(
+    mut commands: Commands,
+    mut pickup_q: Query<(Entity, &mut Transform, &Pickup, Has<Magnetized>), Without<Player>>,
+    player_q: Query<&Transform, With<Player>>,
+    stats: Res<PlayerStats>,
+    mut collected_writer: EventWriter<PickupCollected>,
+    time: Res<Time>,
+) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    let player_pos = player_t.translation.truncate();
+
+    for (pickup_e, mut pickup_t, pickup, magnetized) in pickup_q.iter_mut() {
+        let pickup_pos = pickup_t.translation.truncate();
+        let dist = pickup_pos.distance(player_pos);
+
+        if dist < PICKUP_RADIUS {
+            commands.entity(pickup_e).despawn();
+            collected_writer.send(PickupCollected {
+                kind: pickup.kind,
+                value: pickup.value,
+            });
+        } else if magnetized || dist < stats.magnet {
+            let direction = (player_pos - pickup_pos).normalize_or_zero();
+            let speed = if magnetized { VACUUM_SPEED } else { ATTRACT_SPEED };
+            pickup_t.translation += (direction * speed * time.delta_seconds()).extend(0.0);
+        }
+    }
+}
+
+/// Whether `pos` is inside the view of a camera at `camera_pos`.
+fn on_screen(pos: Vec2, camera_pos: Vec2, window: &Window) -> bool {
+    let offset = (pos - camera_pos).abs();
+    offset.x <= window.width() / 2.0 && offset.y <= window.height() / 2.0
+}
+
+/// What a bomb does to an enemy of `rank`. Bosses are left alone so a bomb can't skip a
+/// boss fight.
+fn bomb_damage(rank: EnemyRank) -> Option<f32> {
+    match rank {
+        EnemyRank::Normal | EnemyRank::Elite => Some(BOMB_DAMAGE),
+        EnemyRank::Boss => None,
+    }
+}
+
+/// Effects of the pickups that don't belong to another module.
+#[allow(clippy::too_many_arguments)]
+fn apply_pickup_effects(
+    mut commands: Commands,
+    mut collected: EventReader<PickupCollected>,
+    mut player_q: Query<&mut Health, With<Player>>,
+    enemy_q: Query<(Entity, &Transform, &Enemy), Without<Player>>,
+    pickup_q: Query<(Entity, &Transform, &Pickup)>,
+    camera_q: Query<&Transform, With<Camera>>,
+    window_q: Query<&Window>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut freeze: ResMut<EnemyFreeze>,
+    mut shake: ResMut<CameraShake>,
+) {
+    let view = camera_q
+        .get_single()
+        .ok()
+        .zip(window_q.get_single().ok())
+        .map(|(camera_t, window)| (camera_t.translation.truncate(), window));
+    let visible = |pos: Vec3| {
+        view.is_some_and(|(camera_pos, window)| on_screen(pos.truncate(), camera_pos, window))
+    };
+
+    for ev in collected.read() {
+        match ev.kind {
+            PickupKind::Food => {
+                if let Ok(mut health) = player_q.get_single_mut() {
+                    health.current = (health.current + FOOD_HEAL).min(health.max);
+                }
+            }
+            PickupKind::Magnet => {
+                for (pickup_e, pickup_t, pickup) in pickup_q.iter() {
+                    if pickup.kind == PickupKind::Xp && visible(pickup_t.translation) {
+                        commands.entity(pickup_e).insert(Magnetized);
+                    }
+                }
+            }
+            PickupKind::Bomb => {
+                for (enemy_e, enemy_t, enemy) in enemy_q.iter() {
+                    if !visible(enemy_t.translation) {
+                        continue;
+                    }
+                    if let Some(amount) = bomb_damage(enemy.rank) {
+                        // A plain physical hit, so resistances still apply
+                        damage_writer.send(DamageEvent {
+                            entity: enemy_e,
+                            amount,
+                            source: None,
+                            damage_type: DamageType::Physical,
+                            crit: false,
+                            knockback: Vec2::ZERO,
+                            weapon: None,
+                        });
+                    }
+                }
+                shake.add_trauma(0.6);
+            }
+            PickupKind::Clock => freeze.start(FREEZE_DURATION),
+            // Handled by their own modules
+            PickupKind::Xp | PickupKind::Gold | PickupKind::Chest => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bombs_spare_bosses() {
+        assert_eq!(bomb_damage(EnemyRank::Normal), Some(BOMB_DAMAGE));
+        assert_eq!(bomb_damage(EnemyRank::Elite), Some(BOMB_DAMAGE));
+        assert_eq!(bomb_damage(EnemyRank::Boss), None);
+    }
+}
@@ -1,6 +1,13 @@
 use bevy::prelude::*;
 
-use crate::health::{DamageCooldown, Health};
+use crate::{
+    character::SelectedCharacter,
+    dash::{Dash, DashStats},
+    health::{DamageCooldown, Health},
+    input::ActionState,
+    obstacle::{self, Obstacle},
+    state::{GameState, PauseState},
+};
 
 #[derive(Component)]
 pub struct Player;
@@ -9,11 +16,26 @@ pub struct Player;
 #[derive(Resource)]
 pub struct PlayerStats {
     pub move_speed: f32,
+    pub max_health: f32,
+    pub gold_multiplier: f32,
+    /// Distance from which pickups start drifting towards the player.
+    pub magnet: f32,
+    /// Multiplies the odds of rare rewards, such as bigger chests.
+    pub luck: f32,
+    /// Multiplies the reach of area weapons like the aura and orbiting tomes.
+    pub area: f32,
 }
 
 impl Default for PlayerStats {
     fn default() -> Self {
-        Self { move_speed: 200.0 }
+        Self {
+            move_speed: 200.0,
+            max_health: 100.0,
+            gold_multiplier: 1.0,
+            magnet: 150.0,
+            luck: 1.0,
+            area: 1.0,
+        }
     }
 }
 
@@ -24,28 +46,31 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
             .insert_resource(PlayerStats::default())
-            .add_systems(Startup, spawn_player)
-            .add_systems(Update, player_movement);
+            .add_systems(Update, player_movement.run_if(in_state(PauseState::Running)));
     }
 }
 
 
 
 
-fn spawn_player(
+pub fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    dash_stats: Res<DashStats>,
+    stats: Res<PlayerStats>,
+    selected: Res<SelectedCharacter>,
 ) {
+    let def = selected.0.definition();
 
-    let texture = asset_server.load("Wizard.png");
+    let texture = asset_server.load(def.texture_path);
 
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(100), 8, 7, None, None);
+    let layout = TextureAtlasLayout::from_grid(def.frame_size, def.columns, def.rows, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     commands.spawn((
         SpriteBundle {
-            transform: Transform::from_xyz(0., 0., 0.).with_scale(Vec3::splat(1.5)),
+            transform: Transform::from_xyz(0., 0., 0.).with_scale(Vec3::splat(def.scale)),
             texture: texture.clone(),
             ..default()
         },
@@ -54,32 +79,26 @@ fn spawn_player(
             index: 0,
         },
         Player,
-        Health::new(100.0),
+        Health::new(stats.max_health),
         Name::new("Player"),
         DamageCooldown::new(0.0),
+        Dash::new(&dash_stats),
+        StateScoped(GameState::Playing),
     ));
 }
 
 pub fn player_movement(
-    kb: Res<ButtonInput<KeyCode>>,
-    mut q: Query<&mut Transform, With<Player>>,
+    actions: Res<ActionState>,
+    mut q: Query<(&mut Transform, &Dash), With<Player>>,
+    obstacle_q: Query<&Obstacle>,
     time: Res<Time>,
     stats: Res<PlayerStats>,
 ) {
-    let mut transform = q.single_mut();
-    let mut dir = Vec2::ZERO;
-    if kb.pressed(KeyCode::KeyW) {
-        dir.y += 1.0;
-    }
-    if kb.pressed(KeyCode::KeyS) {
-        dir.y -= 1.0;
-    }
-    if kb.pressed(KeyCode::KeyA) {
-        dir.x -= 1.0;
+    let (mut transform, dash) = q.single_mut();
+    if dash.is_dashing() {
+        return;
     }
-    if kb.pressed(KeyCode::KeyD) {
-        dir.x += 1.0;
-    }
-
-    transform.translation += (dir.normalize_or_zero() * stats.move_speed * time.delta_seconds()).extend(0.0);
+    let target = transform.translation.truncate() + actions.move_axis * stats.move_speed * time.delta_seconds();
+    let pos = obstacle::resolve(target, obstacle::BODY_RADIUS, &obstacle_q);
+    transform.translation = pos.extend(transform.translation.z);
 }
@@ -0,0 +1,82 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    achievement::AchievementId, enemy::EnemyType, gold::RunGold, save, shop::PowerUp,
+    state::GameState,
+};
+
+/// Everything that survives between runs, stored as `profile.ron` in the data dir.
+/// Changing this struct means bumping `save::CURRENT_VERSION` and adding a migration.
+#[derive(Resource, Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub gold: u32,
+    pub lifetime_gold: u32,
+    pub power_ups: HashMap<PowerUp, u32>,
+    /// Lifetime kills per enemy type.
+    pub kills: HashMap<EnemyType, u32>,
+    pub achievements: HashSet<AchievementId>,
+}
+
+impl Profile {
+    pub fn rank(&self, power_up: PowerUp) -> u32 {
+        self.power_ups.get(&power_up).copied().unwrap_or(0)
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("survivors").join("profile.ron"))
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match save::load(&path) {
+            Ok(profile) => profile.unwrap_or_default(),
+            Err(err) => {
+                // Keep the damaged file around instead of overwriting it on the next save.
+                let aside = path.with_extension("ron.corrupt");
+                warn!(
+                    "Profile {} is unreadable ({}), moved to {}",
+                    path.display(),
+                    err,
+                    aside.display()
+                );
+                let _ = fs::rename(&path, aside);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        if let Err(err) = save::store(&path, self) {
+            warn!("Could not save profile to {}: {}", path.display(), err);
+        }
+    }
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Profile::load())
+            .add_systems(OnEnter(GameState::GameOver), bank_run_gold);
+    }
+}
+
+fn bank_run_gold(mut profile: ResMut<Profile>, run_gold: Res<RunGold>) {
+    profile.gold += run_gold.0;
+    profile.lifetime_gold += run_gold.0;
+    profile.save();
+}
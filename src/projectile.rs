@@ -1,23 +1,67 @@
+use crate::dot::{ApplyDotEvent, DotKind};
 use crate::enemy::Enemy;
-use crate::health::{DamageEvent};
-use crate::weapon::WeaponStats;
-use bevy::prelude::*;
+use crate::player::Player;
+use crate::health::{DamageEvent, DamageType};
+use crate::state::RunRng;
+use crate::weapon::{WeaponKind, WeaponStats};
+use bevy::{prelude::*, utils::HashSet};
+use serde::{Deserialize, Serialize};
 
 #[derive(Component, Reflect)]
 pub struct Projectile {
     pub direction: Vec2,
     pub kind: ProjectileKind,
     pub damage: f32,
+    /// Enemies already hit; piercing shots hit each enemy at most once.
+    pub hit: HashSet<Entity>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Reflect)]
+const HIT_RADIUS: f32 = 20.0;
+
+impl Projectile {
+    /// Whether this projectile at `pos` strikes `enemy` at `enemy_pos`: touching it, and
+    /// not having hit it before.
+    pub fn strikes(&self, pos: Vec3, enemy: Entity, enemy_pos: Vec3) -> bool {
+        !self.hit.contains(&enemy) && pos.distance(enemy_pos) < HIT_RADIUS
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
 pub enum ProjectileKind {
+    #[default]
     Normal,
     Fireball,
     Ice,
     Piercing,
 }
 
+impl ProjectileKind {
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            ProjectileKind::Normal | ProjectileKind::Piercing => DamageType::Physical,
+            ProjectileKind::Fireball => DamageType::Fire,
+            ProjectileKind::Ice => DamageType::Ice,
+        }
+    }
+
+    pub fn knockback(&self) -> f32 {
+        match self {
+            ProjectileKind::Fireball => 150.0,
+            ProjectileKind::Piercing => 20.0,
+            _ => 80.0,
+        }
+    }
+
+    /// DoT left on every enemy hit, with its tick damage as a fraction of the hit.
+    pub fn dot(&self) -> Option<(DotKind, f32)> {
+        match self {
+            ProjectileKind::Fireball => Some((DotKind::Burning, 0.2)),
+            ProjectileKind::Piercing => Some((DotKind::Poison, 0.1)),
+            _ => None,
+        }
+    }
+}
+
 pub fn projectile_movement(
     mut q: Query<(&mut Transform, &Projectile)>,
     stats: Res<WeaponStats>,
@@ -32,43 +76,58 @@ pub fn projectile_movement(
 pub fn projectile_enemy_collision(
     mut commands: Commands,
     mut damage_writer: EventWriter<DamageEvent>,
-    projectile_q: Query<(Entity, &Transform, &Projectile)>,
+    mut dot_writer: EventWriter<ApplyDotEvent>,
+    mut projectile_q: Query<(Entity, &Transform, &mut Projectile)>,
     enemy_q: Query<(Entity, &Transform), With<Enemy>>,
+    stats: Res<WeaponStats>,
+    mut run_rng: ResMut<RunRng>,
 ) {
-    for (proj_e, proj_t, proj) in projectile_q.iter() {
+    for (proj_e, proj_t, mut proj) in projectile_q.iter_mut() {
         for (enemy_e, enemy_t) in enemy_q.iter() {
-            if proj_t.translation.distance(enemy_t.translation) < 20.0 {
+            if proj.strikes(proj_t.translation, enemy_e, enemy_t.translation) {
+                let (amount, crit) = stats.roll_hit(&mut run_rng.rng, proj.damage);
+                let mut hit = |target: Entity, target_pos: Vec3| {
+                    let away = (target_pos - proj_t.translation).truncate().normalize_or(proj.direction);
+                    damage_writer.send(DamageEvent {
+                        entity: target,
+                        amount,
+                        source: Some(proj_e),
+                        damage_type: proj.kind.damage_type(),
+                        crit,
+                        knockback: away * proj.kind.knockback(),
+                        weapon: Some(WeaponKind::Shot(proj.kind)),
+                    });
+                    if let Some((kind, fraction)) = proj.kind.dot() {
+                        dot_writer.send(ApplyDotEvent {
+                            entity: target,
+                            kind,
+                            damage_per_tick: proj.damage * stats.damage_multiplier * fraction,
+                            source: Some(proj_e),
+                            weapon: Some(WeaponKind::Shot(proj.kind)),
+                        });
+                    }
+                };
+
                 match proj.kind {
                     ProjectileKind::Normal => {
-                        damage_writer.send(DamageEvent {
-                            entity: enemy_e,
-                            amount: proj.damage,
-                        });
+                        hit(enemy_e, enemy_t.translation);
                         commands.entity(proj_e).despawn();
                     }
                     ProjectileKind::Fireball => {
                         for (e, t) in enemy_q.iter() {
                             if t.translation.distance(proj_t.translation) < 50.0 {
-                                damage_writer.send(DamageEvent {
-                                    entity: e,
-                                    amount: proj.damage,
-                                });
+                                hit(e, t.translation);
                             }
                         }
                         commands.entity(proj_e).despawn();
                     }
                     ProjectileKind::Piercing => {
-                        damage_writer.send(DamageEvent {
-                            entity: enemy_e,
-                            amount: proj.damage,
-                        });
+                        hit(enemy_e, enemy_t.translation);
                         // projectile stays alive
+                        proj.hit.insert(enemy_e);
                     }
                     ProjectileKind::Ice => {
-                        damage_writer.send(DamageEvent {
-                            entity: enemy_e,
-                            amount: proj.damage,
-                        });
+                        hit(enemy_e, enemy_t.translation);
                         commands.entity(proj_e).despawn();
                     }
                 }
@@ -81,11 +140,58 @@ pub fn projectile_enemy_collision(
 pub fn projectile_bounds_cleanup(
     mut commands: Commands,
     q: Query<(Entity, &Transform), With<Projectile>>,
+    player_q: Query<&Transform, With<Player>>,
 ) {
+    let Ok(player_t) = player_q.get_single() else {
+        return;
+    };
+    // The world scrolls with the player, so "off-screen" is relative to them
     for (entity, transform) in q.iter() {
-        let pos = transform.translation;
-        if pos.x.abs() > 1000.0 || pos.y.abs() > 1000.0 {
+        let offset = transform.translation - player_t.translation;
+        if offset.x.abs() > 1000.0 || offset.y.abs() > 1000.0 {
             commands.entity(entity).despawn();
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piercing_shot() -> Projectile {
+        Projectile {
+            direction: Vec2::X,
+            kind: ProjectileKind::Piercing,
+            damage: 20.0,
+            hit: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn piercing_shot_hits_each_enemy_once_per_pass() {
+        let mut shot = piercing_shot();
+        let first = Entity::from_raw(1);
+        let second = Entity::from_raw(2);
+        let (first_pos, second_pos) = (Vec3::new(50.0, 0.0, 0.0), Vec3::new(60.0, 0.0, 0.0));
+
+        // Step through both enemies a few pixels per frame, overlapping each for many frames
+        let mut hits = Vec::new();
+        for x in (0..120).step_by(4) {
+            let pos = Vec3::new(x as f32, 0.0, 0.0);
+            for (enemy, enemy_pos) in [(first, first_pos), (second, second_pos)] {
+                if shot.strikes(pos, enemy, enemy_pos) {
+                    shot.hit.insert(enemy);
+                    hits.push(enemy);
+                }
+            }
+        }
+        assert_eq!(hits, vec![first, second]);
+    }
+
+    #[test]
+    fn shots_only_strike_what_they_touch() {
+        let shot = piercing_shot();
+        let enemy = Entity::from_raw(1);
+        assert!(shot.strikes(Vec3::ZERO, enemy, Vec3::new(19.0, 0.0, 0.0)));
+        assert!(!shot.strikes(Vec3::ZERO, enemy, Vec3::new(21.0, 0.0, 0.0)));
+    }
+}
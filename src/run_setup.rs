@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+
+use crate::{
+    character::{CharacterId, SelectedCharacter},
+    menu::{self, MenuActivated},
+    player::PlayerStats,
+    profile::Profile,
+    stage::StageId,
+    state::GameState,
+    weapon::WeaponStats,
+};
+
+/// Optional rules that make a run harder in exchange for more gold.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Modifier {
+    Swarm,
+    Frenzy,
+    ToughHides,
+    GlassCannon,
+}
+
+/// Multipliers a modifier applies; 1.0 leaves a value unchanged.
+pub struct ModifierDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub spawn_interval: f32,
+    pub enemy_speed: f32,
+    pub enemy_health: f32,
+    pub player_health: f32,
+    pub player_damage: f32,
+    /// Added to the gold multiplier.
+    pub gold_bonus: f32,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 4] = [
+        Modifier::Swarm,
+        Modifier::Frenzy,
+        Modifier::ToughHides,
+        Modifier::GlassCannon,
+    ];
+
+    pub fn definition(&self) -> ModifierDefinition {
+        match self {
+            Modifier::Swarm => ModifierDefinition {
+                name: "Swarm",
+                description: "Enemies spawn 40% faster",
+                spawn_interval: 0.6,
+                enemy_speed: 1.0,
+                enemy_health: 1.0,
+                player_health: 1.0,
+                player_damage: 1.0,
+                gold_bonus: 0.25,
+            },
+            Modifier::Frenzy => ModifierDefinition {
+                name: "Frenzy",
+                description: "Enemies move 30% faster",
+                spawn_interval: 1.0,
+                enemy_speed: 1.3,
+                enemy_health: 1.0,
+                player_health: 1.0,
+                player_damage: 1.0,
+                gold_bonus: 0.25,
+            },
+            Modifier::ToughHides => ModifierDefinition {
+                name: "Tough Hides",
+                description: "Enemies have 50% more HP",
+                spawn_interval: 1.0,
+                enemy_speed: 1.0,
+                enemy_health: 1.5,
+                player_health: 1.0,
+                player_damage: 1.0,
+                gold_bonus: 0.25,
+            },
+            Modifier::GlassCannon => ModifierDefinition {
+                name: "Glass Cannon",
+                description: "Half max HP, +50% damage",
+                spawn_interval: 1.0,
+                enemy_speed: 1.0,
+                enemy_health: 1.0,
+                player_health: 0.5,
+                player_damage: 1.5,
+                gold_bonus: 0.2,
+            },
+        }
+    }
+}
+
+/// Stage and modifiers chosen for the next run. Kept between runs so "Play Again"
+/// repeats the same setup.
+#[derive(Resource, Default)]
+pub struct RunSetup {
+    pub stage: StageId,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl RunSetup {
+    /// Product of one multiplier across every active modifier.
+    pub fn multiplier(&self, field: impl Fn(&ModifierDefinition) -> f32) -> f32 {
+        self.modifiers.iter().map(|m| field(&m.definition())).product()
+    }
+
+    fn toggle(&mut self, modifier: Modifier) {
+        match self.modifiers.iter().position(|m| *m == modifier) {
+            Some(index) => {
+                self.modifiers.remove(index);
+            }
+            None => self.modifiers.push(modifier),
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum RunSetupButton {
+    Character,
+    Stage,
+    Modifier(Modifier),
+    Start,
+    Back,
+}
+
+pub struct RunSetupPlugin;
+
+impl Plugin for RunSetupPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunSetup::default())
+            .add_systems(OnEnter(GameState::RunSetup), spawn_run_setup)
+            .add_systems(
+                Update,
+                (handle_run_setup_buttons, update_run_setup_labels)
+                    .chain()
+                    .run_if(in_state(GameState::RunSetup)),
+            );
+    }
+}
+
+/// Applies the chosen modifiers on top of the character and power-up stats.
+pub fn apply_modifiers(
+    setup: Res<RunSetup>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+) {
+    player_stats.max_health *= setup.multiplier(|m| m.player_health);
+    weapon_stats.damage_multiplier *= setup.multiplier(|m| m.player_damage);
+    player_stats.gold_multiplier += setup
+        .modifiers
+        .iter()
+        .map(|m| m.definition().gold_bonus)
+        .sum::<f32>();
+}
+
+fn spawn_run_setup(mut commands: Commands) {
+    let mut buttons = vec![RunSetupButton::Character, RunSetupButton::Stage];
+    buttons.extend(Modifier::ALL.map(RunSetupButton::Modifier));
+    buttons.extend([RunSetupButton::Start, RunSetupButton::Back]);
+
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::RunSetup)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "New Run");
+            for (i, button) in buttons.into_iter().enumerate() {
+                // Labels are filled in by update_run_setup_labels
+                menu::spawn_button(parent, i, "", button);
+            }
+        });
+}
+
+fn handle_run_setup_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&RunSetupButton>,
+    profile: Res<Profile>,
+    mut selected: ResMut<SelectedCharacter>,
+    mut setup: ResMut<RunSetup>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        let Ok(button) = button_q.get(ev.0) else {
+            continue;
+        };
+        match *button {
+            RunSetupButton::Character => {
+                // Step to the next unlocked character, wrapping around.
+                let current = CharacterId::ALL.iter().position(|c| *c == selected.0).unwrap_or(0);
+                if let Some(next) = (1..=CharacterId::ALL.len())
+                    .map(|step| CharacterId::ALL[(current + step) % CharacterId::ALL.len()])
+                    .find(|c| profile.character_lock(*c).is_none())
+                {
+                    selected.0 = next;
+                }
+            }
+            RunSetupButton::Stage => next_state.set(GameState::StageSelect),
+            RunSetupButton::Modifier(modifier) => setup.toggle(modifier),
+            RunSetupButton::Start => next_state.set(GameState::Playing),
+            RunSetupButton::Back => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+fn update_run_setup_labels(
+    selected: Res<SelectedCharacter>,
+    setup: Res<RunSetup>,
+    button_q: Query<(&RunSetupButton, &Children)>,
+    added_q: Query<(), Added<RunSetupButton>>,
+    mut text_q: Query<&mut Text>,
+) {
+    if !selected.is_changed() && !setup.is_changed() && added_q.is_empty() {
+        return;
+    }
+
+    for (button, children) in button_q.iter() {
+        let label = match *button {
+            RunSetupButton::Character => {
+                let def = selected.0.definition();
+                format!("Character: {} - {}", def.name, def.description)
+            }
+            RunSetupButton::Stage => {
+                let def = setup.stage.definition();
+                format!("Stage: {} - {} ({})", def.name, def.description, def.rules())
+            }
+            RunSetupButton::Modifier(modifier) => {
+                let def = modifier.definition();
+                format!(
+                    "[{}] {} - {} (+{:.0}% gold)",
+                    if setup.modifiers.contains(&modifier) { "x" } else { " " },
+                    def.name,
+                    def.description,
+                    def.gold_bonus * 100.0
+                )
+            }
+            RunSetupButton::Start => "Start Run".to_string(),
+            RunSetupButton::Back => "Back".to_string(),
+        };
+        for &child in children {
+            if let Ok(mut text) = text_q.get_mut(child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Versioned, checksummed profile storage.
+//!
+//! A save file is a header line followed by a RON body:
+//!
+//! ```text
+//! survivors-save <version> <fnv1a-64 of body, hex>
+//! ( ...profile... )
+//! ```
+//!
+//! Files written before versioning existed have no header and are read as version 1.
+//! Older versions are decoded into their frozen schema and migrated forward one step
+//! at a time, so adding a version only means freezing the current struct and adding a
+//! `migrate` from it.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::profile::Profile;
+
+pub const CURRENT_VERSION: u32 = 3;
+
+const MAGIC: &str = "survivors-save";
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Parse(String),
+    ChecksumMismatch,
+    UnsupportedVersion(u32),
+    MalformedHeader,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "io error: {}", err),
+            SaveError::Parse(err) => write!(f, "parse error: {}", err),
+            SaveError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            SaveError::UnsupportedVersion(v) => write!(f, "unsupported save version {}", v),
+            SaveError::MalformedHeader => write!(f, "malformed header"),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        SaveError::Parse(err.to_string())
+    }
+}
+
+/// Frozen schemas of older save versions. Never edit these; add a new one instead.
+mod v1 {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use crate::shop::PowerUp;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    pub struct Profile {
+        pub gold: u32,
+        pub power_ups: HashMap<PowerUp, u32>,
+    }
+
+    impl Profile {
+        /// v2 adds `lifetime_gold`; count what was banked plus what was spent.
+        pub fn migrate(self) -> super::v2::Profile {
+            let spent: u32 = self
+                .power_ups
+                .iter()
+                .map(|(p, rank)| (0..*rank).map(|r| p.cost(r)).sum::<u32>())
+                .sum();
+            super::v2::Profile {
+                lifetime_gold: self.gold + spent,
+                gold: self.gold,
+                power_ups: self.power_ups,
+            }
+        }
+    }
+}
+
+mod v2 {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use crate::shop::PowerUp;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    pub struct Profile {
+        pub gold: u32,
+        pub lifetime_gold: u32,
+        pub power_ups: HashMap<PowerUp, u32>,
+    }
+
+    impl Profile {
+        /// v3 adds lifetime kills and achievements, both starting empty.
+        pub fn migrate(self) -> crate::profile::Profile {
+            crate::profile::Profile {
+                gold: self.gold,
+                lifetime_gold: self.lifetime_gold,
+                power_ups: self.power_ups,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+pub fn checksum(body: &str) -> u64 {
+    // FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in body.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+pub fn encode(profile: &Profile) -> Result<String, SaveError> {
+    let body = ron::ser::to_string_pretty(profile, ron::ser::PrettyConfig::default())
+        .map_err(|err| SaveError::Parse(err.to_string()))?;
+    Ok(format!(
+        "{} {} {:016x}\n{}",
+        MAGIC,
+        CURRENT_VERSION,
+        checksum(&body),
+        body
+    ))
+}
+
+pub fn decode(text: &str) -> Result<Profile, SaveError> {
+    let Some(rest) = text.strip_prefix(MAGIC) else {
+        // Pre-versioning file: plain RON, no checksum.
+        return decode_version(1, text);
+    };
+
+    let (header, body) = rest.split_once('\n').ok_or(SaveError::MalformedHeader)?;
+    let mut fields = header.split_whitespace();
+    let version = fields
+        .next()
+        .and_then(|v| v.parse::<u32>().ok())
+        .ok_or(SaveError::MalformedHeader)?;
+    let expected = fields
+        .next()
+        .and_then(|c| u64::from_str_radix(c, 16).ok())
+        .ok_or(SaveError::MalformedHeader)?;
+
+    if checksum(body) != expected {
+        return Err(SaveError::ChecksumMismatch);
+    }
+    decode_version(version, body)
+}
+
+fn decode_version(version: u32, body: &str) -> Result<Profile, SaveError> {
+    match version {
+        1 => Ok(ron::from_str::<v1::Profile>(body)?.migrate().migrate()),
+        2 => Ok(ron::from_str::<v2::Profile>(body)?.migrate()),
+        CURRENT_VERSION => Ok(ron::from_str::<Profile>(body)?),
+        v => Err(SaveError::UnsupportedVersion(v)),
+    }
+}
+
+fn with_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_extension(path, ".bak")
+}
+
+fn read(path: &Path) -> Result<Option<Profile>, SaveError> {
+    match fs::read_to_string(path) {
+        Ok(text) => decode(&text).map(Some),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Reads the save at `path`, falling back to its backup if the main file is missing or
+/// damaged. Returns `Ok(None)` if neither exists.
+pub fn load(path: &Path) -> Result<Option<Profile>, SaveError> {
+    let primary = read(path);
+    if let Ok(Some(profile)) = primary {
+        return Ok(Some(profile));
+    }
+    match read(&backup_path(path)) {
+        Ok(Some(profile)) => Ok(Some(profile)),
+        _ => primary,
+    }
+}
+
+/// Replaces `path` with `contents` without ever leaving a half-written file behind:
+/// the data goes to a synced temp file which is then renamed over the target.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = with_extension(path, ".tmp");
+    {
+        use std::io::Write;
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Writes the profile atomically, keeping the previous save (if it still decodes) as
+/// the backup.
+pub fn store(path: &Path, profile: &Profile) -> Result<(), SaveError> {
+    let contents = encode(profile)?;
+    if let Ok(previous) = fs::read_to_string(path)
+        && decode(&previous).is_ok()
+    {
+        fs::copy(path, backup_path(path))?;
+    }
+    write_atomic(path, &contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{achievement::AchievementId, enemy::EnemyType, shop::PowerUp};
+
+    fn sample() -> Profile {
+        let mut profile = Profile {
+            gold: 120,
+            lifetime_gold: 400,
+            ..Default::default()
+        };
+        profile.power_ups.insert(PowerUp::Might, 2);
+        profile.power_ups.insert(PowerUp::Greed, 1);
+        profile.kills.insert(EnemyType::Orc, 42);
+        profile.achievements.insert(AchievementId::Survivor);
+        profile
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("survivors-save-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(backup_path(&path));
+        path
+    }
+
+    #[test]
+    fn current_version_round_trips() {
+        let profile = sample();
+        let text = encode(&profile).unwrap();
+        assert!(text.starts_with(&format!("{} {} ", MAGIC, CURRENT_VERSION)));
+        assert_eq!(decode(&text).unwrap(), profile);
+    }
+
+    #[test]
+    fn v1_migrates_to_current() {
+        let text = "(gold: 50, power_ups: {Might: 2})";
+        let profile = decode(text).unwrap();
+        assert_eq!(profile.gold, 50);
+        assert_eq!(profile.rank(PowerUp::Might), 2);
+        // 50 banked + 20 + 40 spent on two ranks of Might
+        assert_eq!(profile.lifetime_gold, 110);
+        assert_eq!(decode(&encode(&profile).unwrap()).unwrap(), profile);
+    }
+
+    #[test]
+    fn v2_migrates_to_current() {
+        let body = "(gold: 7, lifetime_gold: 90, power_ups: {Greed: 1})";
+        let text = format!("{} 2 {:016x}\n{}", MAGIC, checksum(body), body);
+        let profile = decode(&text).unwrap();
+        assert_eq!(profile.gold, 7);
+        assert_eq!(profile.lifetime_gold, 90);
+        assert_eq!(profile.rank(PowerUp::Greed), 1);
+        assert!(profile.kills.is_empty());
+        assert!(profile.achievements.is_empty());
+        assert_eq!(decode(&encode(&profile).unwrap()).unwrap(), profile);
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let text = encode(&sample()).unwrap().replace("gold: 120", "gold: 999");
+        assert!(matches!(decode(&text), Err(SaveError::ChecksumMismatch)));
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let body = "()";
+        let text = format!("{} 99 {:016x}\n{}", MAGIC, checksum(body), body);
+        assert!(matches!(decode(&text), Err(SaveError::UnsupportedVersion(99))));
+    }
+
+    #[test]
+    fn corrupt_save_falls_back_to_backup() {
+        let path = temp_path("fallback.ron");
+        let first = sample();
+        let mut second = sample();
+        second.gold = 5;
+
+        store(&path, &first).unwrap();
+        store(&path, &second).unwrap();
+        assert_eq!(load(&path).unwrap(), Some(second));
+
+        fs::write(&path, "survivors-save 2 0000\ngarbage").unwrap();
+        assert_eq!(load(&path).unwrap(), Some(first));
+    }
+
+    #[test]
+    fn missing_save_loads_nothing() {
+        let path = temp_path("missing.ron");
+        assert_eq!(load(&path).unwrap(), None);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    audio::{GlobalVolume, Volume},
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    damage_numbers::DamageNumberSettings,
+    menu::{self, MenuActivated},
+    save,
+};
+
+const RESOLUTIONS: [(u32, u32); 5] = [
+    (800, 600),
+    (1024, 768),
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+];
+
+/// Player preferences, loaded from and saved to `settings.ron` in the config dir.
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 0..1
+    pub volume: f32,
+    pub fullscreen: bool,
+    /// Window size when not fullscreen.
+    pub resolution: (u32, u32),
+    pub damage_numbers: bool,
+    /// 0 disables screen shake, 1 is full strength.
+    pub screen_shake: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 0.8,
+            fullscreen: false,
+            resolution: RESOLUTIONS[0],
+            damage_numbers: true,
+            screen_shake: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("survivors").join("settings.ron"))
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => ron::from_str(&text).unwrap_or_else(|err| {
+                warn!("Ignoring invalid settings in {}: {}", path.display(), err);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| save::write_atomic(&path, &text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Could not save settings to {}: {}", path.display(), err);
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum SettingsButton {
+    Volume,
+    Fullscreen,
+    Resolution,
+    DamageNumbers,
+    ScreenShake,
+    Back,
+}
+
+impl SettingsButton {
+    const ALL: [SettingsButton; 6] = [
+        SettingsButton::Volume,
+        SettingsButton::Fullscreen,
+        SettingsButton::Resolution,
+        SettingsButton::DamageNumbers,
+        SettingsButton::ScreenShake,
+        SettingsButton::Back,
+    ];
+
+    fn label(&self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        match self {
+            SettingsButton::Volume => format!("Volume: {:.0}%", settings.volume * 100.0),
+            SettingsButton::Fullscreen => format!("Fullscreen: {}", on_off(settings.fullscreen)),
+            SettingsButton::Resolution => {
+                format!("Resolution: {}x{}", settings.resolution.0, settings.resolution.1)
+            }
+            SettingsButton::DamageNumbers => {
+                format!("Damage Numbers: {}", on_off(settings.damage_numbers))
+            }
+            SettingsButton::ScreenShake => {
+                format!("Screen Shake: {:.0}%", settings.screen_shake * 100.0)
+            }
+            SettingsButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// Sent when the settings screen's Back button is chosen; whoever opened it decides
+/// where to go next.
+#[derive(Event)]
+pub struct SettingsClosed;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .add_event::<SettingsClosed>()
+            .add_systems(
+                Update,
+                (handle_settings_buttons, update_settings_labels, apply_settings).chain(),
+            );
+    }
+}
+
+/// Spawns the settings screen, despawned when `scope` is left.
+pub fn spawn_settings_screen<S: States>(commands: &mut Commands, scope: S) {
+    commands
+        .spawn((menu::root_node(), StateScoped(scope)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Settings");
+            for (i, button) in SettingsButton::ALL.into_iter().enumerate() {
+                // Labels are filled in by update_settings_labels
+                menu::spawn_button(parent, i, "", button);
+            }
+        });
+}
+
+fn handle_settings_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&SettingsButton>,
+    mut settings: ResMut<Settings>,
+    mut closed: EventWriter<SettingsClosed>,
+) {
+    for ev in activated.read() {
+        let Ok(button) = button_q.get(ev.0) else {
+            continue;
+        };
+        // Each press steps to the next value, wrapping around.
+        match button {
+            SettingsButton::Volume => {
+                let step = (settings.volume * 10.0).round() as u32;
+                settings.volume = ((step + 1) % 11) as f32 / 10.0;
+            }
+            SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsButton::Resolution => {
+                let current = RESOLUTIONS.iter().position(|r| *r == settings.resolution);
+                settings.resolution =
+                    RESOLUTIONS[current.map_or(0, |i| (i + 1) % RESOLUTIONS.len())];
+            }
+            SettingsButton::DamageNumbers => settings.damage_numbers = !settings.damage_numbers,
+            SettingsButton::ScreenShake => {
+                let step = (settings.screen_shake * 2.0).round() as u32;
+                settings.screen_shake = ((step + 1) % 3) as f32 / 2.0;
+            }
+            SettingsButton::Back => {
+                closed.send(SettingsClosed);
+                continue;
+            }
+        }
+        settings.save();
+    }
+}
+
+fn update_settings_labels(
+    settings: Res<Settings>,
+    button_q: Query<(&SettingsButton, &Children)>,
+    added_q: Query<(), Added<SettingsButton>>,
+    mut text_q: Query<&mut Text>,
+) {
+    if !settings.is_changed() && added_q.is_empty() {
+        return;
+    }
+
+    for (button, children) in button_q.iter() {
+        for &child in children {
+            if let Ok(mut text) = text_q.get_mut(child) {
+                text.sections[0].value = button.label(&settings);
+            }
+        }
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut volume: ResMut<GlobalVolume>,
+    mut damage_numbers: ResMut<DamageNumberSettings>,
+    mut window_q: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    volume.volume = Volume::new(settings.volume);
+    damage_numbers.enabled = settings.damage_numbers;
+    for mut window in window_q.iter_mut() {
+        window.mode = if settings.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        };
+        let (width, height) = settings.resolution;
+        window.resolution.set(width as f32, height as f32);
+    }
+}
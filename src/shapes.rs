@@ -0,0 +1,40 @@
+//! Plain textures generated at startup for sprites that don't need artwork.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+/// White disc, tinted by whatever uses it. Plugins that draw with it call
+/// `init_resource::<CircleTexture>()`, so it is only built once.
+#[derive(Resource)]
+pub struct CircleTexture(pub Handle<Image>);
+
+impl FromWorld for CircleTexture {
+    fn from_world(world: &mut World) -> Self {
+        const SIZE: u32 = 64;
+        let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let offset = Vec2::new(x as f32, y as f32) + 0.5 - SIZE as f32 / 2.0;
+                let alpha = if offset.length() <= SIZE as f32 / 2.0 { 255 } else { 0 };
+                data.extend_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+        let image = Image::new(
+            Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        Self(world.resource_mut::<Assets<Image>>().add(image))
+    }
+}
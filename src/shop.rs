@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    menu::{self, MenuActivated},
+    player::PlayerStats,
+    profile::Profile,
+    state::GameState,
+    weapon::WeaponStats,
+};
+
+/// Permanent bonuses bought with gold between runs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PowerUp {
+    Might,
+    MaxHealth,
+    MoveSpeed,
+    ShotSpeed,
+    Greed,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 5] = [
+        PowerUp::Might,
+        PowerUp::MaxHealth,
+        PowerUp::MoveSpeed,
+        PowerUp::ShotSpeed,
+        PowerUp::Greed,
+    ];
+}
+
+pub struct PowerUpDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub max_rank: u32,
+    pub base_cost: u32,
+}
+
+impl PowerUp {
+    pub fn definition(&self) -> PowerUpDefinition {
+        match self {
+            PowerUp::Might => PowerUpDefinition {
+                name: "Might",
+                description: "+10% damage",
+                max_rank: 5,
+                base_cost: 20,
+            },
+            PowerUp::MaxHealth => PowerUpDefinition {
+                name: "Max Health",
+                description: "+10 max HP",
+                max_rank: 5,
+                base_cost: 15,
+            },
+            PowerUp::MoveSpeed => PowerUpDefinition {
+                name: "Move Speed",
+                description: "+10 move speed",
+                max_rank: 3,
+                base_cost: 25,
+            },
+            PowerUp::ShotSpeed => PowerUpDefinition {
+                name: "Shot Speed",
+                description: "+25 shot speed",
+                max_rank: 3,
+                base_cost: 15,
+            },
+            PowerUp::Greed => PowerUpDefinition {
+                name: "Greed",
+                description: "+20% gold",
+                max_rank: 5,
+                base_cost: 30,
+            },
+        }
+    }
+
+    /// Gold needed to go from `rank` to `rank + 1`.
+    pub fn cost(&self, rank: u32) -> u32 {
+        self.definition().base_cost * (rank + 1)
+    }
+}
+
+/// Applies the profile's purchased power ups to freshly reset run stats.
+pub fn apply_power_ups(
+    profile: Res<Profile>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut weapon_stats: ResMut<WeaponStats>,
+) {
+    for power_up in PowerUp::ALL {
+        let rank = profile.rank(power_up) as f32;
+        match power_up {
+            PowerUp::Might => weapon_stats.damage_multiplier += 0.1 * rank,
+            PowerUp::MaxHealth => player_stats.max_health += 10.0 * rank,
+            PowerUp::MoveSpeed => player_stats.move_speed += 10.0 * rank,
+            PowerUp::ShotSpeed => weapon_stats.projectile_speed += 25.0 * rank,
+            PowerUp::Greed => player_stats.gold_multiplier += 0.2 * rank,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum ShopButton {
+    Buy(PowerUp),
+    Refund,
+    Back,
+}
+
+#[derive(Component)]
+struct ShopGoldText;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::PowerUps), spawn_shop)
+            .add_systems(
+                Update,
+                (handle_shop_buttons, update_shop_labels)
+                    .chain()
+                    .run_if(in_state(GameState::PowerUps)),
+            );
+    }
+}
+
+fn spawn_shop(mut commands: Commands) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::PowerUps)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Power Ups");
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(1.0, 0.85, 0.2),
+                        ..default()
+                    },
+                ),
+                ShopGoldText,
+            ));
+            for (i, power_up) in PowerUp::ALL.into_iter().enumerate() {
+                menu::spawn_button(parent, i, "", ShopButton::Buy(power_up));
+            }
+            let n = PowerUp::ALL.len();
+            menu::spawn_button(parent, n, "", ShopButton::Refund);
+            menu::spawn_button(parent, n + 1, "", ShopButton::Back);
+        });
+}
+
+fn handle_shop_buttons(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&ShopButton>,
+    mut profile: ResMut<Profile>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        let Ok(button) = button_q.get(ev.0) else {
+            continue;
+        };
+        match *button {
+            ShopButton::Buy(power_up) => {
+                let rank = profile.rank(power_up);
+                let cost = power_up.cost(rank);
+                if rank < power_up.definition().max_rank && profile.gold >= cost {
+                    profile.gold -= cost;
+                    profile.power_ups.insert(power_up, rank + 1);
+                    profile.save();
+                }
+            }
+            ShopButton::Refund => {
+                let refund: u32 = PowerUp::ALL
+                    .iter()
+                    .map(|p| (0..profile.rank(*p)).map(|r| p.cost(r)).sum::<u32>())
+                    .sum();
+                profile.gold += refund;
+                profile.power_ups.clear();
+                profile.save();
+            }
+            ShopButton::Back => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+fn update_shop_labels(
+    profile: Res<Profile>,
+    button_q: Query<(&ShopButton, &Children)>,
+    added_q: Query<(), Added<ShopButton>>,
+    mut gold_text_q: Query<&mut Text, With<ShopGoldText>>,
+    mut text_q: Query<&mut Text, Without<ShopGoldText>>,
+) {
+    if !profile.is_changed() && added_q.is_empty() {
+        return;
+    }
+
+    for mut text in gold_text_q.iter_mut() {
+        text.sections[0].value = format!(
+            "Gold: {} (lifetime {})",
+            profile.gold, profile.lifetime_gold
+        );
+    }
+
+    for (button, children) in button_q.iter() {
+        let label = match *button {
+            ShopButton::Buy(power_up) => {
+                let def = power_up.definition();
+                let rank = profile.rank(power_up);
+                if rank >= def.max_rank {
+                    format!("{} ({}) - MAX", def.name, def.description)
+                } else {
+                    format!(
+                        "{} ({}) {}/{} - {}g",
+                        def.name,
+                        def.description,
+                        rank,
+                        def.max_rank,
+                        power_up.cost(rank)
+                    )
+                }
+            }
+            ShopButton::Refund => "Refund All".to_string(),
+            ShopButton::Back => "Back".to_string(),
+        };
+        for &child in children {
+            if let Ok(mut text) = text_q.get_mut(child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Stages: where a run takes place and the rules it plays by. Everything that used to be
+//! fixed for every run (ground, obstacles, which enemies come and how fast) is read from
+//! the selected stage's definition.
+
+use bevy::prelude::*;
+
+use crate::{
+    background::Ground,
+    enemy::EnemyType,
+    menu::{self, MenuActivated},
+    obstacle::{ObstacleKind, ObstacleLayout},
+    run_setup::RunSetup,
+    state::{GameState, PauseState, RunStats, RunTimer},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect)]
+pub enum StageId {
+    #[default]
+    Field,
+    Arena,
+}
+
+/// One step of a stage's wave timeline, in effect from `start` until the next wave.
+pub struct Wave {
+    /// Seconds into the run.
+    pub start: f32,
+    /// Seconds between spawns, before modifiers.
+    pub spawn_interval: f32,
+    /// Enemies that can spawn, with relative weights.
+    pub enemies: &'static [(EnemyType, u32)],
+    /// Chance of each spawn being an elite.
+    pub elite_chance: f32,
+    /// Spawned once as the wave starts.
+    pub boss: Option<EnemyType>,
+}
+
+pub enum StageBounds {
+    /// The ground goes on forever.
+    Endless,
+    /// Play is walled in to a rectangle centred on the start.
+    Arena { half_size: Vec2 },
+}
+
+pub struct StageDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub ground: Ground,
+    pub obstacles: ObstacleLayout,
+    /// Chance of a breakable brazier on any given tile.
+    pub light_sources: f32,
+    /// Ordered by start time; the first wave must start at 0.
+    pub waves: &'static [Wave],
+    /// Looping track played for the whole run.
+    pub music: Option<&'static str>,
+    /// Surviving this long (in seconds) clears the stage; `None` runs until death.
+    pub duration: Option<f32>,
+    pub bounds: StageBounds,
+}
+
+impl StageDefinition {
+    pub fn wave_at(&self, elapsed: f32) -> &'static Wave {
+        &self.waves[self.wave_index_at(elapsed)]
+    }
+
+    /// Index into `waves` of the wave in effect at `elapsed`.
+    pub fn wave_index_at(&self, elapsed: f32) -> usize {
+        self.waves
+            .iter()
+            .rposition(|wave| wave.start <= elapsed)
+            .unwrap_or(0)
+    }
+
+    /// Short summary of the stage's rules for menus.
+    pub fn rules(&self) -> String {
+        let bounds = match self.bounds {
+            StageBounds::Endless => "endless",
+            StageBounds::Arena { .. } => "walled arena",
+        };
+        match self.duration {
+            Some(duration) => {
+                let secs = duration as u32;
+                format!("{}, survive {:02}:{:02}", bounds, secs / 60, secs % 60)
+            }
+            None => format!("{}, no time limit", bounds),
+        }
+    }
+}
+
+/// Pillars around the centre and a wall across each end of the arena.
+const ARENA_OBSTACLES: &[(ObstacleKind, Vec2)] = &[
+    (ObstacleKind::Rock, Vec2::new(-400.0, 400.0)),
+    (ObstacleKind::Rock, Vec2::new(400.0, 400.0)),
+    (ObstacleKind::Rock, Vec2::new(-400.0, -400.0)),
+    (ObstacleKind::Rock, Vec2::new(400.0, -400.0)),
+    (ObstacleKind::Wall, Vec2::new(0.0, 640.0)),
+    (ObstacleKind::Wall, Vec2::new(0.0, -640.0)),
+];
+
+impl StageId {
+    pub const ALL: [StageId; 2] = [StageId::Field, StageId::Arena];
+
+    pub fn definition(&self) -> StageDefinition {
+        match self {
+            StageId::Field => StageDefinition {
+                name: "Open Field",
+                description: "Endless grassland",
+                ground: Ground::Grass,
+                obstacles: ObstacleLayout::Scattered(&[
+                    (ObstacleKind::Tree, 0.012),
+                    (ObstacleKind::Rock, 0.008),
+                    (ObstacleKind::Wall, 0.001),
+                ]),
+                light_sources: 0.0015,
+                waves: &[
+                    Wave {
+                        start: 0.0,
+                        spawn_interval: 1.2,
+                        enemies: &[(EnemyType::Skeleton, 1)],
+                        elite_chance: 0.0,
+                        boss: None,
+                    },
+                    Wave {
+                        start: 45.0,
+                        spawn_interval: 0.9,
+                        enemies: &[(EnemyType::Skeleton, 3), (EnemyType::Orc, 1)],
+                        elite_chance: 0.01,
+                        boss: None,
+                    },
+                    Wave {
+                        start: 120.0,
+                        spawn_interval: 0.65,
+                        enemies: &[
+                            (EnemyType::Skeleton, 2),
+                            (EnemyType::Orc, 2),
+                            (EnemyType::Werewolf, 1),
+                        ],
+                        elite_chance: 0.015,
+                        boss: None,
+                    },
+                    Wave {
+                        start: 240.0,
+                        spawn_interval: 0.5,
+                        enemies: &[
+                            (EnemyType::Skeleton, 1),
+                            (EnemyType::Orc, 2),
+                            (EnemyType::Werewolf, 2),
+                        ],
+                        elite_chance: 0.02,
+                        boss: Some(EnemyType::Orc),
+                    },
+                    Wave {
+                        start: 420.0,
+                        spawn_interval: 0.35,
+                        enemies: &[
+                            (EnemyType::Skeleton, 1),
+                            (EnemyType::Orc, 1),
+                            (EnemyType::Werewolf, 2),
+                        ],
+                        elite_chance: 0.03,
+                        boss: Some(EnemyType::Werewolf),
+                    },
+                ],
+                music: None,
+                duration: None,
+                bounds: StageBounds::Endless,
+            },
+            StageId::Arena => StageDefinition {
+                name: "Colosseum",
+                description: "Nowhere to run",
+                ground: Ground::Flagstone,
+                obstacles: ObstacleLayout::Fixed(ARENA_OBSTACLES),
+                light_sources: 0.003,
+                waves: &[
+                    Wave {
+                        start: 0.0,
+                        spawn_interval: 0.8,
+                        enemies: &[(EnemyType::Skeleton, 3), (EnemyType::Orc, 1)],
+                        elite_chance: 0.01,
+                        boss: None,
+                    },
+                    Wave {
+                        start: 60.0,
+                        spawn_interval: 0.6,
+                        enemies: &[(EnemyType::Orc, 2), (EnemyType::Werewolf, 1)],
+                        elite_chance: 0.02,
+                        boss: None,
+                    },
+                    Wave {
+                        start: 180.0,
+                        spawn_interval: 0.4,
+                        enemies: &[(EnemyType::Orc, 1), (EnemyType::Werewolf, 2)],
+                        elite_chance: 0.03,
+                        boss: Some(EnemyType::Orc),
+                    },
+                    Wave {
+                        start: 360.0,
+                        spawn_interval: 0.3,
+                        enemies: &[(EnemyType::Werewolf, 1)],
+                        elite_chance: 0.04,
+                        boss: Some(EnemyType::Werewolf),
+                    },
+                ],
+                music: None,
+                duration: Some(480.0),
+                bounds: StageBounds::Arena {
+                    half_size: Vec2::splat(1024.0),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+enum StageSelectButton {
+    Stage(StageId),
+    Back,
+}
+
+pub struct StagePlugin;
+
+impl Plugin for StagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::StageSelect), spawn_stage_select)
+            .add_systems(OnEnter(GameState::Playing), play_stage_music)
+            .add_systems(
+                Update,
+                handle_stage_select.run_if(in_state(GameState::StageSelect)),
+            )
+            .add_systems(
+                Update,
+                check_stage_cleared.run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn spawn_stage_select(mut commands: Commands, setup: Res<RunSetup>) {
+    commands
+        .spawn((menu::root_node(), StateScoped(GameState::StageSelect)))
+        .with_children(|parent| {
+            menu::spawn_title(parent, "Choose a stage");
+            for (i, id) in StageId::ALL.into_iter().enumerate() {
+                let def = id.definition();
+                let label = format!(
+                    "{}. {}{} - {} ({})",
+                    i + 1,
+                    def.name,
+                    if setup.stage == id { " [selected]" } else { "" },
+                    def.description,
+                    def.rules()
+                );
+                menu::spawn_button(parent, i, label, StageSelectButton::Stage(id));
+            }
+            let n = StageId::ALL.len();
+            menu::spawn_button(parent, n, format!("{}. Back", n + 1), StageSelectButton::Back);
+        });
+}
+
+fn handle_stage_select(
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&StageSelectButton>,
+    mut setup: ResMut<RunSetup>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ev in activated.read() {
+        match button_q.get(ev.0) {
+            Ok(StageSelectButton::Stage(id)) => {
+                setup.stage = *id;
+                next_state.set(GameState::RunSetup);
+            }
+            Ok(StageSelectButton::Back) => next_state.set(GameState::RunSetup),
+            Err(_) => {}
+        }
+    }
+}
+
+fn play_stage_music(mut commands: Commands, setup: Res<RunSetup>, asset_server: Res<AssetServer>) {
+    if let Some(path) = setup.stage.definition().music {
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(path),
+                settings: PlaybackSettings::LOOP,
+            },
+            StateScoped(GameState::Playing),
+        ));
+    }
+}
+
+/// Ends the run as a win once a timed stage has been survived to the end.
+fn check_stage_cleared(
+    setup: Res<RunSetup>,
+    run_timer: Res<RunTimer>,
+    mut run_stats: ResMut<RunStats>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(duration) = setup.stage.definition().duration else {
+        return;
+    };
+    if !run_stats.cleared && run_timer.0.elapsed_secs() >= duration {
+        run_stats.cleared = true;
+        next_state.set(GameState::GameOver);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_wave_starts_at_zero() {
+        for id in StageId::ALL {
+            let def = id.definition();
+            assert_eq!(def.waves[0].start, 0.0, "{}", def.name);
+            assert!(std::ptr::eq(def.wave_at(0.0), &def.waves[0]));
+        }
+    }
+
+    #[test]
+    fn wave_takes_over_exactly_at_its_start() {
+        for id in StageId::ALL {
+            let def = id.definition();
+            for pair in def.waves.windows(2) {
+                let (previous, next) = (&pair[0], &pair[1]);
+                assert!(std::ptr::eq(def.wave_at(next.start - 0.01), previous));
+                assert!(std::ptr::eq(def.wave_at(next.start), next));
+            }
+        }
+    }
+
+    #[test]
+    fn last_wave_lasts_forever() {
+        for id in StageId::ALL {
+            let def = id.definition();
+            assert!(std::ptr::eq(def.wave_at(1.0e6), def.waves.last().unwrap()));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::{
+    prelude::*,
+    time::Stopwatch,
+    utils::{HashMap, HashSet},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{enemy::EnemyType, projectile::ProjectileKind};
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    RunSetup,
+    StageSelect,
+    Characters,
+    PowerUps,
+    Achievements,
+    Settings,
+    Leaderboard,
+    Playing,
+    GameOver,
+}
+
+/// Whether a run in progress is paused. Only exists while `GameState::Playing`.
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[source(GameState = GameState::Playing)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    Settings,
+    /// A treasure chest is being opened.
+    Chest,
+    /// An upgrade is being picked after a level up.
+    LevelUp,
+}
+
+/// Time survived in the current run.
+#[derive(Resource, Default)]
+pub struct RunTimer(pub Stopwatch);
+
+/// Tallies for the current run, reset when a new run starts.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub kills: HashMap<EnemyType, u32>,
+    /// Every shot type the player has fired with this run.
+    pub shot_types: HashSet<ProjectileKind>,
+    /// What last damaged the player, reported as the cause of death.
+    pub last_hit_by: Option<&'static str>,
+    /// Set when a timed stage was survived to the end.
+    pub cleared: bool,
+    /// Set when the player ended the run from the pause menu.
+    pub quit: bool,
+}
+
+/// Seeded RNG for everything random in a run, so a run can be identified by its seed.
+#[derive(Resource)]
+pub struct RunRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RunRng {
+    fn default() -> Self {
+        Self::new(rand::thread_rng().r#gen())
+    }
+}
+
+pub fn tick_run_timer(time: Res<Time>, mut timer: ResMut<RunTimer>) {
+    timer.0.tick(time.delta());
+}
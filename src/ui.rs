@@ -1,13 +1,21 @@
 use bevy::{color::palettes::css, prelude::*};
 
-use crate::level::PlayerXP;
+use crate::{
+    gold::RunGold,
+    health::Health,
+    level::PlayerXP,
+    player::Player,
+    state::{GameState, RunStats, RunTimer},
+    upgrade::{UpgradeLevels, UpgradeSlot},
+    weapon::WeaponStats,
+};
 
 #[derive(Component)]
 pub struct XpBarFill; // Tag for the "fill" part of the bar
 
 pub fn setup_xp_bar(mut commands: Commands) {
     commands
-        .spawn(NodeBundle {
+        .spawn((NodeBundle {
             style: Style {
                 width: Val::Percent(100.0),
                 height: Val::Px(20.0),
@@ -21,7 +29,7 @@ pub fn setup_xp_bar(mut commands: Commands) {
             },
             background_color: css::DARK_GRAY.into(),
             ..default()
-        })
+        }, StateScoped(GameState::Playing)))
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
@@ -47,3 +55,210 @@ pub fn update_xp_bar(xp: Res<PlayerXP>, mut query: Query<&mut Style, With<XpBarF
         style.width = Val::Percent(percent * 100.0);
     }
 }
+
+#[derive(Component)]
+pub struct HudTimerText;
+
+#[derive(Component)]
+pub struct HudStatsText;
+
+#[derive(Component)]
+pub struct HudHealthFill;
+
+#[derive(Component)]
+pub struct HudHealthText;
+
+#[derive(Component)]
+pub struct HudSlots;
+
+fn hud_text(size: f32) -> TextStyle {
+    TextStyle {
+        font_size: size,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+pub fn setup_hud(mut commands: Commands) {
+    // Top-left: player health
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(200.0),
+                        height: Val::Px(14.0),
+                        ..default()
+                    },
+                    background_color: css::DARK_GRAY.into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: css::RED.into(),
+                            ..default()
+                        },
+                        HudHealthFill,
+                    ));
+                });
+            parent.spawn((TextBundle::from_section("", hud_text(16.0)), HudHealthText));
+        });
+
+    // Top-centre: survival timer
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(8.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section("00:00", hud_text(32.0)), HudTimerText));
+        });
+
+    // Top-right: level, kills, gold, then owned weapon/passive slots
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(GameState::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section("", hud_text(18.0)), HudStatsText));
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::FlexEnd,
+                        max_width: Val::Px(300.0),
+                        margin: UiRect::top(Val::Px(4.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                HudSlots,
+            ));
+        });
+}
+
+pub fn update_hud_text(
+    run_timer: Res<RunTimer>,
+    run_stats: Res<RunStats>,
+    run_gold: Res<RunGold>,
+    xp: Res<PlayerXP>,
+    mut timer_q: Query<&mut Text, (With<HudTimerText>, Without<HudStatsText>)>,
+    mut stats_q: Query<&mut Text, (With<HudStatsText>, Without<HudTimerText>)>,
+) {
+    let secs = run_timer.0.elapsed_secs() as u32;
+    for mut text in timer_q.iter_mut() {
+        text.sections[0].value = format!("{:02}:{:02}", secs / 60, secs % 60);
+    }
+
+    let kills: u32 = run_stats.kills.values().sum();
+    for mut text in stats_q.iter_mut() {
+        text.sections[0].value = format!("Lv {}   Kills {}   Gold {}", xp.level, kills, run_gold.0);
+    }
+}
+
+pub fn update_hud_health(
+    player_q: Query<&Health, With<Player>>,
+    mut fill_q: Query<&mut Style, With<HudHealthFill>>,
+    mut text_q: Query<&mut Text, With<HudHealthText>>,
+) {
+    let Ok(health) = player_q.get_single() else {
+        return;
+    };
+    let ratio = (health.current / health.max).clamp(0.0, 1.0);
+    for mut style in fill_q.iter_mut() {
+        style.width = Val::Percent(ratio * 100.0);
+    }
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!("{:.0} / {:.0}", health.current.max(0.0), health.max);
+    }
+}
+
+pub fn update_hud_slots(
+    mut commands: Commands,
+    levels: Res<UpgradeLevels>,
+    weapon_stats: Res<WeaponStats>,
+    slots_q: Query<Entity, With<HudSlots>>,
+    added_q: Query<(), Added<HudSlots>>,
+) {
+    if !levels.is_changed() && !weapon_stats.is_changed() && added_q.is_empty() {
+        return;
+    }
+    let Ok(slots_e) = slots_q.get_single() else {
+        return;
+    };
+
+    // Weapons first, then passives, each in the order they were picked up.
+    let mut slots = levels.0.clone();
+    slots.sort_by_key(|(slot, _)| !slot.is_weapon());
+
+    commands.entity(slots_e).despawn_descendants().with_children(|parent| {
+        for (slot, level) in slots {
+            let color = match slot {
+                UpgradeSlot::Weapon(kind) if kind == weapon_stats.current_shot_type => {
+                    Color::srgb(0.8, 0.4, 0.1)
+                }
+                UpgradeSlot::Weapon(_) => Color::srgb(0.3, 0.2, 0.1),
+                UpgradeSlot::Orbit | UpgradeSlot::Aura => Color::srgb(0.8, 0.4, 0.1),
+                _ => Color::srgb(0.2, 0.3, 0.5),
+            };
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(54.0),
+                        height: Val::Px(36.0),
+                        margin: UiRect::all(Val::Px(2.0)),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: color.into(),
+                    ..default()
+                })
+                .with_children(|icon| {
+                    icon.spawn(TextBundle::from_section(slot.abbreviation(), hud_text(13.0)));
+                    icon.spawn(TextBundle::from_section(format!("Lv{}", level), hud_text(12.0)));
+                });
+        }
+    });
+}
@@ -1,15 +1,196 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use crate::projectile::ProjectileKind;
+use crate::{
+    aura::AuraStats, dash::DashStats, level::PlayerXP, orbit::OrbitStats, player::PlayerStats,
+    projectile::ProjectileKind, weapon::WeaponStats,
+};
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum UpgradeEffect {
     IncreaseMultishot(u32),
     IncreaseSpread(f32),
     IncreaseProjectileSpeed(f32),
     IncreaseMoveSpeed(f32),
     IncreaseXPGain(u32),
+    IncreaseMagnet(f32),
     ChangeShotType(ProjectileKind),
+    AddOrbiter(u32),
+    IncreaseAuraDamage(f32),
+    IncreaseArea(f32),
+    IncreaseDashCharges(u32),
+    ReduceDashCooldown(f32),
+    IncreaseDashTrailDamage(f32),
+}
+
+/// What an upgrade levels up. Picking the same kind of upgrade again raises its level.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UpgradeSlot {
+    Weapon(ProjectileKind),
+    Orbit,
+    Aura,
+    Multishot,
+    Spread,
+    ShotSpeed,
+    MoveSpeed,
+    XPGain,
+    Magnet,
+    Area,
+    DashCharges,
+    DashCooldown,
+    DashTrail,
+}
+
+/// Every upgrade that can be offered on level up, before unlock checks.
+pub const UPGRADE_POOL: [UpgradeEffect; 15] = [
+    UpgradeEffect::IncreaseMultishot(1),
+    UpgradeEffect::IncreaseSpread(10.0),
+    UpgradeEffect::IncreaseProjectileSpeed(100.0),
+    UpgradeEffect::IncreaseMoveSpeed(50.0),
+    UpgradeEffect::IncreaseXPGain(1),
+    UpgradeEffect::IncreaseMagnet(50.0),
+    UpgradeEffect::IncreaseArea(0.1),
+    UpgradeEffect::ChangeShotType(ProjectileKind::Fireball),
+    UpgradeEffect::ChangeShotType(ProjectileKind::Ice),
+    UpgradeEffect::ChangeShotType(ProjectileKind::Piercing),
+    UpgradeEffect::AddOrbiter(1),
+    UpgradeEffect::IncreaseAuraDamage(5.0),
+    UpgradeEffect::IncreaseDashCharges(1),
+    UpgradeEffect::ReduceDashCooldown(0.25),
+    UpgradeEffect::IncreaseDashTrailDamage(15.0),
+];
+
+impl UpgradeEffect {
+    pub fn label(&self) -> String {
+        match self {
+            UpgradeEffect::ChangeShotType(kind) => match kind {
+                ProjectileKind::Fireball => "Fireball Shot".to_string(),
+                ProjectileKind::Ice => "Ice Shot".to_string(),
+                ProjectileKind::Piercing => "Piercing Shot".to_string(),
+                ProjectileKind::Normal => "Normal Shot".to_string(),
+            },
+            UpgradeEffect::AddOrbiter(n) => format!("+{} Orbiting Tome", n),
+            UpgradeEffect::IncreaseAuraDamage(d) => format!("+{} Aura Damage", d),
+            UpgradeEffect::IncreaseMultishot(n) => format!("+{} Multishot", n),
+            UpgradeEffect::IncreaseSpread(s) => format!("+{}° Spread", s),
+            UpgradeEffect::IncreaseProjectileSpeed(s) => format!("+{} Shot Speed", s),
+            UpgradeEffect::IncreaseMoveSpeed(s) => format!("+{} Move Speed", s),
+            UpgradeEffect::IncreaseXPGain(n) => format!("+{} XP per Orb", n),
+            UpgradeEffect::IncreaseMagnet(r) => format!("+{} Pickup Range", r),
+            UpgradeEffect::IncreaseArea(a) => format!("+{}% Area", (a * 100.0).round()),
+            UpgradeEffect::IncreaseDashCharges(n) => format!("+{} Dash Charge", n),
+            UpgradeEffect::ReduceDashCooldown(s) => format!("-{}s Dash Cooldown", s),
+            UpgradeEffect::IncreaseDashTrailDamage(d) => format!("+{} Dash Trail Damage", d),
+        }
+    }
+
+    pub fn slot(&self) -> UpgradeSlot {
+        match self {
+            UpgradeEffect::IncreaseMultishot(_) => UpgradeSlot::Multishot,
+            UpgradeEffect::IncreaseSpread(_) => UpgradeSlot::Spread,
+            UpgradeEffect::IncreaseProjectileSpeed(_) => UpgradeSlot::ShotSpeed,
+            UpgradeEffect::IncreaseMoveSpeed(_) => UpgradeSlot::MoveSpeed,
+            UpgradeEffect::IncreaseXPGain(_) => UpgradeSlot::XPGain,
+            UpgradeEffect::IncreaseMagnet(_) => UpgradeSlot::Magnet,
+            UpgradeEffect::IncreaseArea(_) => UpgradeSlot::Area,
+            UpgradeEffect::ChangeShotType(kind) => UpgradeSlot::Weapon(*kind),
+            UpgradeEffect::AddOrbiter(_) => UpgradeSlot::Orbit,
+            UpgradeEffect::IncreaseAuraDamage(_) => UpgradeSlot::Aura,
+            UpgradeEffect::IncreaseDashCharges(_) => UpgradeSlot::DashCharges,
+            UpgradeEffect::ReduceDashCooldown(_) => UpgradeSlot::DashCooldown,
+            UpgradeEffect::IncreaseDashTrailDamage(_) => UpgradeSlot::DashTrail,
+        }
+    }
+}
+
+impl UpgradeSlot {
+    /// Short label used on HUD slot icons.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            UpgradeSlot::Weapon(ProjectileKind::Normal) => "Bolt",
+            UpgradeSlot::Weapon(ProjectileKind::Fireball) => "Fire",
+            UpgradeSlot::Weapon(ProjectileKind::Ice) => "Ice",
+            UpgradeSlot::Weapon(ProjectileKind::Piercing) => "Pierce",
+            UpgradeSlot::Orbit => "Tome",
+            UpgradeSlot::Aura => "Aura",
+            UpgradeSlot::Multishot => "Multi",
+            UpgradeSlot::Spread => "Spread",
+            UpgradeSlot::ShotSpeed => "Speed",
+            UpgradeSlot::MoveSpeed => "Boots",
+            UpgradeSlot::XPGain => "Growth",
+            UpgradeSlot::Magnet => "Magnet",
+            UpgradeSlot::Area => "Area",
+            UpgradeSlot::DashCharges => "Dash",
+            UpgradeSlot::DashCooldown => "Haste",
+            UpgradeSlot::DashTrail => "Trail",
+        }
+    }
+
+    pub fn is_weapon(&self) -> bool {
+        matches!(self, UpgradeSlot::Weapon(_) | UpgradeSlot::Orbit | UpgradeSlot::Aura)
+    }
+
+    /// The upgrade that levels this slot up once more.
+    pub fn level_up_effect(&self) -> Option<UpgradeEffect> {
+        match self {
+            UpgradeSlot::Weapon(kind) => Some(UpgradeEffect::ChangeShotType(*kind)),
+            _ => UPGRADE_POOL.into_iter().find(|effect| effect.slot() == *self),
+        }
+    }
+}
+
+/// Levels of everything the player owns this run, in the order it was acquired.
+#[derive(Resource, Default)]
+pub struct UpgradeLevels(pub Vec<(UpgradeSlot, u32)>);
+
+impl UpgradeLevels {
+    /// Current level of `slot`, 0 if not owned.
+    pub fn level(&self, slot: UpgradeSlot) -> u32 {
+        self.0.iter().find(|(s, _)| *s == slot).map_or(0, |(_, level)| *level)
+    }
+
+    pub fn add(&mut self, slot: UpgradeSlot) {
+        match self.0.iter_mut().find(|(s, _)| *s == slot) {
+            Some((_, level)) => *level += 1,
+            None => self.0.push((slot, 1)),
+        }
+    }
+}
+
+/// Everything an upgrade can change, so level-ups and chests apply upgrades the same way.
+#[derive(SystemParam)]
+pub struct UpgradeTargets<'w> {
+    pub weapon_stats: ResMut<'w, WeaponStats>,
+    pub player_stats: ResMut<'w, PlayerStats>,
+    pub dash_stats: ResMut<'w, DashStats>,
+    pub orbit_stats: ResMut<'w, OrbitStats>,
+    pub aura_stats: ResMut<'w, AuraStats>,
+    pub xp: ResMut<'w, PlayerXP>,
+    pub levels: ResMut<'w, UpgradeLevels>,
+}
+
+impl UpgradeTargets<'_> {
+    pub fn apply_upgrade(&mut self, effect: &UpgradeEffect) {
+        match effect {
+            UpgradeEffect::ChangeShotType(kind) => {
+                self.weapon_stats.current_shot_type = *kind;
+            }
+            UpgradeEffect::AddOrbiter(n) => self.orbit_stats.count += n,
+            UpgradeEffect::IncreaseAuraDamage(d) => self.aura_stats.damage += d,
+            UpgradeEffect::IncreaseMultishot(n) => self.weapon_stats.multishot += n,
+            UpgradeEffect::IncreaseSpread(s) => self.weapon_stats.spread_deg += s,
+            UpgradeEffect::IncreaseProjectileSpeed(s) => self.weapon_stats.projectile_speed += s,
+            UpgradeEffect::IncreaseMoveSpeed(s) => self.player_stats.move_speed += s,
+            UpgradeEffect::IncreaseXPGain(x) => self.xp.orb_value += x,
+            UpgradeEffect::IncreaseMagnet(r) => self.player_stats.magnet += r,
+            UpgradeEffect::IncreaseArea(a) => self.player_stats.area += a,
+            UpgradeEffect::IncreaseDashCharges(n) => self.dash_stats.max_charges += n,
+            UpgradeEffect::ReduceDashCooldown(s) => {
+                self.dash_stats.cooldown = (self.dash_stats.cooldown - s).max(0.3);
+            }
+            UpgradeEffect::IncreaseDashTrailDamage(d) => self.dash_stats.trail_damage += d,
+        }
+        self.levels.add(effect.slot());
+    }
 }
 
 #[derive(Component)]
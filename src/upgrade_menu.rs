@@ -1,124 +1,84 @@
-use crate::level::{IsUpgradeMenuOpen, LevelUpEvent, PlayerXP};
-use crate::player::PlayerStats;
-use crate::projectile::ProjectileKind;
-use crate::upgrade::{UpgradeButton, UpgradeEffect, UpgradeMenuRoot};
-use crate::weapon::WeaponStats;
-use bevy::color::palettes::css;
+use crate::level::{LevelUpEvent, PendingLevelUps};
+use crate::menu::{self, MenuActivated};
+use crate::profile::Profile;
+use crate::state::{PauseState, RunRng};
+use crate::upgrade::{UpgradeButton, UpgradeMenuRoot, UpgradeTargets, UPGRADE_POOL};
 use bevy::prelude::*;
 use rand::seq::SliceRandom;
 
-pub fn show_upgrade_menu(
-    mut commands: Commands,
+/// Counts new level ups and pauses the run to pick their upgrades.
+pub fn queue_level_ups(
     mut ev_levelup: EventReader<LevelUpEvent>,
-    mut open: ResMut<IsUpgradeMenuOpen>,
+    mut pending: ResMut<PendingLevelUps>,
+    mut next_state: ResMut<NextState<PauseState>>,
 ) {
-    if ev_levelup.is_empty() || open.0 {
-        return;
+    pending.0 += ev_levelup.read().count() as u32;
+    // A chest opened this frame goes first; the level ups stay pending until it is closed
+    if pending.0 > 0 && matches!(*next_state, NextState::Unchanged) {
+        next_state.set(PauseState::LevelUp);
     }
-    ev_levelup.clear();
-    open.0 = true;
+}
+
+pub fn open_upgrade_menu(
+    mut commands: Commands,
+    mut pending: ResMut<PendingLevelUps>,
+    profile: Res<Profile>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    pending.0 = pending.0.saturating_sub(1);
+    spawn_upgrade_menu(&mut commands, &profile, &mut run_rng);
+}
 
-    let all_upgrades = vec![
-        UpgradeEffect::IncreaseMultishot(1),
-        UpgradeEffect::IncreaseSpread(10.0),
-        UpgradeEffect::IncreaseProjectileSpeed(100.0),
-        UpgradeEffect::IncreaseMoveSpeed(50.0),
-        UpgradeEffect::IncreaseXPGain(1),
-        UpgradeEffect::ChangeShotType(ProjectileKind::Fireball),
-        UpgradeEffect::ChangeShotType(ProjectileKind::Ice),
-        UpgradeEffect::ChangeShotType(ProjectileKind::Piercing),
-    ];
+fn spawn_upgrade_menu(commands: &mut Commands, profile: &Profile, run_rng: &mut RunRng) {
+    let available = UPGRADE_POOL
+        .into_iter()
+        .filter(|u| profile.is_upgrade_unlocked(u))
+        .collect::<Vec<_>>();
 
-    let mut rng = rand::thread_rng();
-    let selected = all_upgrades
-        .choose_multiple(&mut rng, 3)
+    let selected = available
+        .choose_multiple(&mut run_rng.rng, 3)
         .cloned()
         .collect::<Vec<_>>();
 
     commands
-        .spawn(NodeBundle {
-            style: Style {
-                // size: Size::width(Val::Percent(100.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
-            ..default()
-        })
-        .insert(UpgradeMenuRoot)
+        .spawn((menu::root_node(), UpgradeMenuRoot, StateScoped(PauseState::LevelUp)))
         .with_children(|parent| {
-            for upgrade in selected {
-                let label = match &upgrade {
-                    UpgradeEffect::ChangeShotType(kind) => match kind {
-                        ProjectileKind::Fireball => "Fireball Shot".to_string(),
-                        ProjectileKind::Ice => "Ice Shot".to_string(),
-                        ProjectileKind::Piercing => "Piercing Shot".to_string(),
-                        ProjectileKind::Normal => "Normal Shot".to_string(),
-                    },
-                    UpgradeEffect::IncreaseMultishot(n) => format!("+{} Multishot", n),
-                    UpgradeEffect::IncreaseSpread(s) => format!("+{}° Spread", s),
-                    UpgradeEffect::IncreaseProjectileSpeed(s) => format!("+{} Shot Speed", s),
-                    UpgradeEffect::IncreaseMoveSpeed(s) => format!("+{} Move Speed", s),
-                    UpgradeEffect::IncreaseXPGain(n) => format!("+{} XP per Orb", n),
-                };
-
-                parent
-                    .spawn(ButtonBundle {
-                        style: Style {
-                            // size: Size::new(Val::Px(220.0), Val::Px(50.0)),
-                            margin: UiRect::all(Val::Px(10.0)),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        background_color: css::DARK_GRAY.into(),
-                        ..default()
-                    })
-                    .insert(UpgradeButton(upgrade.clone()))
-                    .with_children(|p| {
-                        p.spawn(TextBundle::from_section(
-                            label,
-                            TextStyle {
-                                //   font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                font_size: 24.0,
-                                color: Color::WHITE,
-                                ..default()
-                            },
-                        ));
-                    });
+            menu::spawn_title(parent, "Level Up!");
+            for (i, upgrade) in selected.into_iter().enumerate() {
+                let label = format!("{}. {}", i + 1, upgrade.label());
+                menu::spawn_button(parent, i, label, UpgradeButton(upgrade));
             }
         });
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_upgrade_selection(
     mut commands: Commands,
-    mut interaction_q: Query<(&Interaction, &UpgradeButton, Entity), Changed<Interaction>>,
-    mut weapon_stats: ResMut<WeaponStats>,
-    mut player_stats: ResMut<PlayerStats>,
-    mut xp: ResMut<PlayerXP>,
-    mut open: ResMut<IsUpgradeMenuOpen>,
+    mut activated: EventReader<MenuActivated>,
+    button_q: Query<&UpgradeButton>,
+    mut targets: UpgradeTargets,
+    mut pending: ResMut<PendingLevelUps>,
+    profile: Res<Profile>,
+    mut run_rng: ResMut<RunRng>,
+    mut next_state: ResMut<NextState<PauseState>>,
     upgrade_menu_roots: Query<Entity, With<UpgradeMenuRoot>>,
 ) {
-    for (interaction, button, _entity) in interaction_q.iter_mut() {
-        if *interaction == Interaction::Pressed {
-            match &button.0 {
-                UpgradeEffect::ChangeShotType(kind) => {
-                    weapon_stats.current_shot_type = *kind;
-                }
-                UpgradeEffect::IncreaseMultishot(n) => weapon_stats.multishot += n,
-                UpgradeEffect::IncreaseSpread(s) => weapon_stats.spread_deg += s,
-                UpgradeEffect::IncreaseProjectileSpeed(s) => weapon_stats.projectile_speed += s,
-                UpgradeEffect::IncreaseMoveSpeed(s) => player_stats.move_speed += s,
-                UpgradeEffect::IncreaseXPGain(x) => xp.orb_value += x,
-            }
+    for ev in activated.read() {
+        if let Ok(button) = button_q.get(ev.0) {
+            targets.apply_upgrade(&button.0);
 
-            let menu_roots = upgrade_menu_roots.iter();
-            for e in menu_roots {
+            for e in upgrade_menu_roots.iter() {
                 commands.entity(e).despawn_recursive();
             }
 
-            open.0 = false;
+            // The menu reopens straight away until every level up has had its upgrade
+            if pending.0 > 0 {
+                pending.0 -= 1;
+                spawn_upgrade_menu(&mut commands, &profile, &mut run_rng);
+            } else {
+                next_state.set(PauseState::Running);
+            }
+            break;
         }
     }
 }
@@ -1,7 +1,10 @@
 use crate::enemy::Enemy;
+use crate::evolution;
 use crate::player::Player;
 use crate::projectile::{Projectile, ProjectileKind};
-use bevy::prelude::*;
+use crate::state::{GameState, RunStats};
+use bevy::{prelude::*, utils::HashSet};
+use rand::Rng;
 
 #[derive(Resource)]
 pub struct WeaponTimer(pub Timer);
@@ -12,6 +15,29 @@ impl Default for WeaponTimer {
     }
 }
 
+/// Everything the player deals damage with, for attributing damage and kills.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect)]
+pub enum WeaponKind {
+    Shot(ProjectileKind),
+    DashTrail,
+    Orbit,
+    Aura,
+}
+
+impl WeaponKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponKind::Shot(ProjectileKind::Normal) => "Magic Bolt",
+            WeaponKind::Shot(ProjectileKind::Fireball) => "Fireball",
+            WeaponKind::Shot(ProjectileKind::Ice) => "Ice Shard",
+            WeaponKind::Shot(ProjectileKind::Piercing) => "Piercing Shot",
+            WeaponKind::DashTrail => "Dash Trail",
+            WeaponKind::Orbit => "Orbiting Tomes",
+            WeaponKind::Aura => "Aura",
+        }
+    }
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct WeaponStats {
@@ -19,6 +45,11 @@ pub struct WeaponStats {
     pub spread_deg: f32, // Total arc of spread
     pub projectile_speed: f32,
     pub current_shot_type: ProjectileKind,
+    pub damage_multiplier: f32,
+    pub crit_chance: f32, // 0..1, rolled per hit
+    pub crit_multiplier: f32,
+    /// Shot types evolved this run.
+    pub evolved: Vec<ProjectileKind>,
 }
 
 impl Default for WeaponStats {
@@ -28,6 +59,24 @@ impl Default for WeaponStats {
             spread_deg: 10.0,
             projectile_speed: 300.0,
             current_shot_type: ProjectileKind::Normal,
+            damage_multiplier: 1.0,
+            crit_chance: 0.05,
+            crit_multiplier: 2.0,
+            evolved: Vec::new(),
+        }
+    }
+}
+
+impl WeaponStats {
+    /// Damage of one hit from a weapon dealing `base`, after the damage multiplier and a
+    /// crit roll. Every weapon hit goes through here so they all scale and crit alike.
+    pub fn roll_hit(&self, rng: &mut impl Rng, base: f32) -> (f32, bool) {
+        let crit = rng.gen_bool(self.crit_chance.clamp(0.0, 1.0) as f64);
+        let amount = base * self.damage_multiplier;
+        if crit {
+            (amount * self.crit_multiplier, true)
+        } else {
+            (amount, false)
         }
     }
 }
@@ -37,6 +86,7 @@ pub fn weapon_system(
     time: Res<Time>,
     mut timer: ResMut<WeaponTimer>,
     stats: Res<WeaponStats>,
+    mut run_stats: ResMut<RunStats>,
     player_q: Query<&Transform, With<Player>>,
     enemy_q: Query<&Transform, With<Enemy>>,
 ) {
@@ -44,6 +94,7 @@ pub fn weapon_system(
 
     if timer.0.finished() {
         let player_transform = player_q.single();
+        run_stats.shot_types.insert(stats.current_shot_type);
 
         let mut nearest: Option<Vec2> = None;
         let mut min_dist_sq = f32::MAX;
@@ -87,13 +138,20 @@ pub fn weapon_system(
                 Projectile {
                     direction: rotated.normalize_or_zero(),
                     kind: stats.current_shot_type,
+                    // The damage multiplier and crits are applied on hit
                     damage: match stats.current_shot_type {
                         ProjectileKind::Normal => 25.0,
                         ProjectileKind::Fireball => 40.0,
                         ProjectileKind::Piercing => 20.0,
                         ProjectileKind::Ice => 15.0,
+                    } * if stats.evolved.contains(&stats.current_shot_type) {
+                        evolution::EVOLVED_DAMAGE
+                    } else {
+                        1.0
                     },
+                    hit: HashSet::new(),
                 },
+                StateScoped(GameState::Playing),
             ));
         }
     }